@@ -0,0 +1,16 @@
+-- Table: public.anilist_cache
+
+-- DROP TABLE IF EXISTS public.anilist_cache;
+
+CREATE TABLE IF NOT EXISTS public.anilist_cache
+(
+    key text COLLATE pg_catalog."default" NOT NULL,
+    response text COLLATE pg_catalog."default" NOT NULL,
+    fetched_at timestamp with time zone NOT NULL DEFAULT now(),
+    CONSTRAINT pk_anilist_cache PRIMARY KEY (key)
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE IF EXISTS public.anilist_cache
+    OWNER to postgres;
@@ -0,0 +1,3 @@
+-- Modify table `users` adding a new column `anilist_username` of type `text` holding the AniList account linked with `anilist link`
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS anilist_username text;
@@ -0,0 +1,21 @@
+-- Table: public.anime_subscriptions
+
+-- DROP TABLE IF EXISTS public.anime_subscriptions;
+
+CREATE TABLE IF NOT EXISTS public.anime_subscriptions
+(
+    guild_id text COLLATE pg_catalog."default" NOT NULL,
+    channel_id text COLLATE pg_catalog."default" NOT NULL,
+    media_id bigint NOT NULL,
+    title text COLLATE pg_catalog."default" NOT NULL,
+    last_episode integer NOT NULL DEFAULT 0,
+    -- Unix timestamp the airing schedule has been checked up to, so missed or failed announcements are retried
+    checked_until bigint NOT NULL DEFAULT extract(epoch FROM now())::bigint,
+    created_by text REFERENCES public.users (id) NOT NULL,
+    CONSTRAINT pk_anime_subscriptions PRIMARY KEY (channel_id, media_id)
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE IF EXISTS public.anime_subscriptions
+    OWNER to postgres;
@@ -0,0 +1,16 @@
+-- Table: public.guild_settings
+
+-- DROP TABLE IF EXISTS public.guild_settings;
+
+CREATE TABLE IF NOT EXISTS public.guild_settings
+(
+    guild_id text COLLATE pg_catalog."default" NOT NULL,
+    -- 'channel' shows adult AniList entries in age-restricted channels only, 'allow' and 'block' apply everywhere
+    adult_content text COLLATE pg_catalog."default" NOT NULL DEFAULT 'channel',
+    CONSTRAINT pk_guild_settings PRIMARY KEY (guild_id)
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE IF EXISTS public.guild_settings
+    OWNER to postgres;
@@ -0,0 +1,26 @@
+-- Table: public.watchlist
+
+-- DROP TABLE IF EXISTS public.watchlist;
+
+CREATE TABLE IF NOT EXISTS public.watchlist
+(
+    user_id text REFERENCES public.users (id) NOT NULL,
+    media_id bigint NOT NULL,
+    -- AniList MediaType, ANIME or MANGA
+    media_type text COLLATE pg_catalog."default" NOT NULL,
+    title text COLLATE pg_catalog."default" NOT NULL,
+    -- planning, watching, completed or dropped
+    status text COLLATE pg_catalog."default" NOT NULL DEFAULT 'planning',
+    progress integer NOT NULL DEFAULT 0,
+    -- Episode or chapter count, NULL while AniList doesn't know it yet
+    total integer,
+    score integer,
+    updated_at timestamp with time zone NOT NULL DEFAULT now(),
+    CONSTRAINT pk_watchlist PRIMARY KEY (user_id, media_id),
+    CONSTRAINT watchlist_score_range CHECK (score BETWEEN 0 AND 10)
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE IF EXISTS public.watchlist
+    OWNER to postgres;
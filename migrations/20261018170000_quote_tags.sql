@@ -0,0 +1,20 @@
+-- Table: public.quote_tags
+
+-- DROP TABLE IF EXISTS public.quote_tags;
+
+CREATE TABLE IF NOT EXISTS public.quote_tags
+(
+    quote_id text COLLATE pg_catalog."default" REFERENCES public.quotes (id) ON DELETE CASCADE NOT NULL,
+    tag character varying(32) COLLATE pg_catalog."default" NOT NULL,
+    CONSTRAINT pk_quote_tags PRIMARY KEY (quote_id, tag)
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE IF EXISTS public.quote_tags
+    OWNER to postgres;
+
+CREATE INDEX IF NOT EXISTS idx_quote_tags_tag
+    ON public.quote_tags USING btree
+    (tag COLLATE pg_catalog."default" ASC NULLS LAST)
+    TABLESPACE pg_default;
@@ -0,0 +1,16 @@
+-- Full-text search over quotes, with trigram indexes for fuzzy matches when the full-text search finds nothing
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+ALTER TABLE quotes ADD COLUMN IF NOT EXISTS search tsvector
+    GENERATED ALWAYS AS (to_tsvector('english', quote)) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_quotes_search
+    ON public.quotes USING gin
+    (search)
+    TABLESPACE pg_default;
+
+CREATE INDEX IF NOT EXISTS idx_quotes_quote_trgm
+    ON public.quotes USING gin
+    (quote gin_trgm_ops)
+    TABLESPACE pg_default;
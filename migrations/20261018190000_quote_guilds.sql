@@ -0,0 +1,36 @@
+-- Scope quotes to the guild they were added in.
+-- The new column is NULL for every existing quote, which marks it as global so it stays visible everywhere.
+
+ALTER TABLE quotes ADD COLUMN IF NOT EXISTS guild_id text COLLATE pg_catalog."default";
+
+CREATE INDEX IF NOT EXISTS idx_quotes_guild_id
+    ON public.quotes USING btree
+    (guild_id COLLATE pg_catalog."default" ASC NULLS LAST)
+    TABLESPACE pg_default;
+
+-- Guilds that opt in see each others quotes
+ALTER TABLE guild_settings ADD COLUMN IF NOT EXISTS share_quotes boolean NOT NULL DEFAULT false;
+
+-- FUNCTION: public.quote_visible(text, text)
+
+-- DROP FUNCTION IF EXISTS public.quote_visible(text, text);
+
+-- Whether a quote from quote_guild can be seen in viewer_guild (dm:<user id> for DMs)
+CREATE OR REPLACE FUNCTION public.quote_visible(
+	quote_guild text,
+	viewer_guild text)
+    RETURNS boolean
+    LANGUAGE 'sql'
+    COST 100
+    STABLE PARALLEL SAFE
+AS $BODY$
+  SELECT quote_guild IS NULL
+    OR quote_guild = viewer_guild
+    OR (
+      EXISTS (SELECT 1 FROM guild_settings WHERE guild_id = viewer_guild AND share_quotes)
+      AND EXISTS (SELECT 1 FROM guild_settings WHERE guild_id = quote_guild AND share_quotes)
+    );
+$BODY$;
+
+ALTER FUNCTION public.quote_visible(text, text)
+    OWNER TO postgres;
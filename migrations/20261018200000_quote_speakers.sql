@@ -0,0 +1,20 @@
+-- Record who said a quote separately from who added it, and where it came from.
+-- author always held the user who ran addquote, so it becomes added_by.
+
+ALTER TABLE quotes RENAME COLUMN author TO added_by;
+
+-- A Discord user, or a free text name for people who aren't on Discord
+ALTER TABLE quotes ADD COLUMN IF NOT EXISTS said_by_id text COLLATE pg_catalog."default";
+ALTER TABLE quotes ADD COLUMN IF NOT EXISTS said_by_name text COLLATE pg_catalog."default";
+
+-- Existing quotes have no known creation time, so the default only applies to new rows
+ALTER TABLE quotes ADD COLUMN IF NOT EXISTS created_at timestamp with time zone;
+ALTER TABLE quotes ALTER COLUMN created_at SET DEFAULT now();
+
+ALTER TABLE quotes ADD COLUMN IF NOT EXISTS channel_id text COLLATE pg_catalog."default";
+ALTER TABLE quotes ADD COLUMN IF NOT EXISTS message_id text COLLATE pg_catalog."default";
+
+CREATE INDEX IF NOT EXISTS idx_quotes_said_by_id
+    ON public.quotes USING btree
+    (said_by_id COLLATE pg_catalog."default" ASC NULLS LAST)
+    TABLESPACE pg_default;
@@ -0,0 +1,6 @@
+-- Link back to the message a quote was taken from, NULL when it was typed out with addquote.
+
+ALTER TABLE quotes ADD COLUMN IF NOT EXISTS jump_url text COLLATE pg_catalog."default";
+
+-- When the quoted message was sent, created_at stays the time the quote was added
+ALTER TABLE quotes ADD COLUMN IF NOT EXISTS said_at timestamp with time zone;
@@ -1,4 +1,4 @@
-use rusted_wumpus_lib::checks::is_admin;
+use rusted_wumpus_lib::checks::{is_admin, AdultContent};
 
 use crate::{Context, Error};
 
@@ -18,3 +18,104 @@ pub async fn register(ctx: Context<'_>) -> Result<(), Error> {
 
     Ok(())
 }
+
+/// Show how often AniList lookups are answered from the cache
+#[poise::command(
+    prefix_command,
+    slash_command,
+    hide_in_help,
+    category = "Admin",
+    check = "is_admin"
+)]
+pub async fn cachestats(ctx: Context<'_>) -> Result<(), Error> {
+    let stats = if let Some(cache) = ctx.data().anilist.cache() {
+        cache.stats()
+    } else {
+        ctx.say("The AniList cache is disabled").await?;
+        return Ok(());
+    };
+
+    let lookups = stats.hits + stats.misses;
+    let hit_rate = if lookups == 0 {
+        0.0
+    } else {
+        stats.hits as f64 / lookups as f64 * 100.0
+    };
+
+    ctx.say(format!(
+        "AniList cache: {} hits, {} misses ({hit_rate:.1}% hit rate), {} entries in memory",
+        stats.hits, stats.misses, stats.entries
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Choose where adult AniList entries can be shown in this server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD",
+    category = "Admin"
+)]
+pub async fn adultcontent(
+    ctx: Context<'_>,
+    #[description = "Where adult entries are shown"] setting: AdultContent,
+) -> Result<(), Error> {
+    let guild_id = if let Some(guild_id) = ctx.guild_id() {
+        guild_id
+    } else {
+        return Ok(());
+    };
+
+    sqlx::query(
+        "INSERT INTO guild_settings (guild_id, adult_content) VALUES ($1, $2)
+        ON CONFLICT (guild_id) DO UPDATE SET adult_content = EXCLUDED.adult_content;",
+    )
+    .bind(guild_id.0.to_string())
+    .bind(setting.as_str())
+    .execute(&ctx.data().db.clone())
+    .await?;
+
+    ctx.say(format!("Adult AniList entries: {setting}")).await?;
+
+    Ok(())
+}
+
+/// Share this server's quotes with every other server that opts in
+#[poise::command(
+    prefix_command,
+    slash_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD",
+    category = "Admin"
+)]
+pub async fn sharequotes(
+    ctx: Context<'_>,
+    #[description = "Share quotes with other servers"] share: bool,
+) -> Result<(), Error> {
+    let guild_id = if let Some(guild_id) = ctx.guild_id() {
+        guild_id
+    } else {
+        return Ok(());
+    };
+
+    sqlx::query(
+        "INSERT INTO guild_settings (guild_id, share_quotes) VALUES ($1, $2)
+        ON CONFLICT (guild_id) DO UPDATE SET share_quotes = EXCLUDED.share_quotes;",
+    )
+    .bind(guild_id.0.to_string())
+    .bind(share)
+    .execute(&ctx.data().db.clone())
+    .await?;
+
+    if share {
+        ctx.say("This server's quotes are now shared with other servers that share theirs")
+            .await?;
+    } else {
+        ctx.say("This server's quotes are no longer shared").await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,199 @@
+use rusted_wumpus_lib::{
+    checks::adult_content_allowed, structs::SubscriptionRow, utils::return_truncated,
+};
+use tracing::instrument;
+
+use super::apis::{autocomplete_anime, lookup_media, ADULT_BLOCKED};
+use crate::{vars::ANIME_QUERY, Context, Error};
+
+/// Get notified in this channel when new episodes air
+#[instrument]
+#[poise::command(
+    prefix_command,
+    slash_command,
+    guild_only,
+    category = "Fun",
+    subcommands("airing_subscribe", "airing_unsubscribe", "airing_list")
+)]
+pub async fn airing(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say("Use `airing subscribe`, `airing unsubscribe` or `airing list`")
+        .await?;
+
+    Ok(())
+}
+
+/// Post a message in this channel whenever a new episode of an anime airs
+#[instrument]
+#[poise::command(
+    prefix_command,
+    slash_command,
+    guild_only,
+    rename = "subscribe",
+    required_permissions = "MANAGE_CHANNELS",
+    category = "Fun"
+)]
+pub async fn airing_subscribe(
+    ctx: Context<'_>,
+    #[description = "Name"]
+    #[autocomplete = "autocomplete_anime"]
+    msg: String,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let guild_id = if let Some(guild_id) = ctx.guild_id() {
+        guild_id
+    } else {
+        return Ok(());
+    };
+
+    let media = if let Some(media) = lookup_media(ctx, ANIME_QUERY, &msg)
+        .await?
+        .into_iter()
+        .next()
+    {
+        media
+    } else {
+        ctx.say(format!("No anime found for `{msg}`")).await?;
+        return Ok(());
+    };
+
+    let title = media.display_title().to_string();
+
+    // Announcements go out no matter who's looking, so adult shows need the channel to allow them up front
+    if media.is_adult == Some(true) && !adult_content_allowed(ctx).await? {
+        ctx.say(ADULT_BLOCKED).await?;
+        return Ok(());
+    }
+
+    // Only shows that are still to air or currently airing will ever send a notification
+    if !matches!(
+        media.status.as_deref(),
+        Some("RELEASING" | "NOT_YET_RELEASED")
+    ) {
+        ctx.say(format!("{title} isn't airing any more episodes"))
+            .await?;
+        return Ok(());
+    }
+
+    let row: Option<SubscriptionRow> = sqlx::query_as(
+        "INSERT INTO anime_subscriptions (guild_id, channel_id, media_id, title, created_by) VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (channel_id, media_id) DO NOTHING RETURNING *;",
+    )
+    .bind(guild_id.0.to_string())
+    .bind(ctx.channel_id().0.to_string())
+    .bind(media.id)
+    .bind(&title)
+    .bind(ctx.author().id.0.to_string())
+    .fetch_optional(&ctx.data().db.clone())
+    .await?;
+
+    if row.is_some() {
+        ctx.say(format!(
+            "New episodes of {title} will be announced in this channel"
+        ))
+        .await?;
+    } else {
+        ctx.say(format!("This channel is already subscribed to {title}"))
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Stop announcing new episodes of an anime in this channel
+#[instrument]
+#[poise::command(
+    prefix_command,
+    slash_command,
+    guild_only,
+    rename = "unsubscribe",
+    required_permissions = "MANAGE_CHANNELS",
+    category = "Fun"
+)]
+pub async fn airing_unsubscribe(
+    ctx: Context<'_>,
+    #[description = "Name"]
+    #[autocomplete = "autocomplete_anime"]
+    msg: String,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let media = if let Some(media) = lookup_media(ctx, ANIME_QUERY, &msg)
+        .await?
+        .into_iter()
+        .next()
+    {
+        media
+    } else {
+        ctx.say(format!("No anime found for `{msg}`")).await?;
+        return Ok(());
+    };
+
+    let row: Option<SubscriptionRow> = sqlx::query_as(
+        "DELETE FROM anime_subscriptions WHERE (channel_id, media_id) = ($1, $2) RETURNING *;",
+    )
+    .bind(ctx.channel_id().0.to_string())
+    .bind(media.id)
+    .fetch_optional(&ctx.data().db.clone())
+    .await?;
+
+    if let Some(row) = row {
+        ctx.say(format!("Unsubscribed this channel from {}", row.title))
+            .await?;
+    } else {
+        ctx.say(format!(
+            "This channel isn't subscribed to {}",
+            media.display_title()
+        ))
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// List the anime this server gets episode notifications for
+#[instrument]
+#[poise::command(
+    prefix_command,
+    slash_command,
+    guild_only,
+    rename = "list",
+    category = "Fun"
+)]
+pub async fn airing_list(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = if let Some(guild_id) = ctx.guild_id() {
+        guild_id
+    } else {
+        return Ok(());
+    };
+
+    let rows: Vec<SubscriptionRow> = sqlx::query_as(
+        "SELECT * FROM anime_subscriptions WHERE (guild_id) = ($1) ORDER BY channel_id, title;",
+    )
+    .bind(guild_id.0.to_string())
+    .fetch_all(&ctx.data().db.clone())
+    .await?;
+
+    if rows.is_empty() {
+        ctx.say("This server isn't subscribed to any anime").await?;
+        return Ok(());
+    }
+
+    let lines: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            format!(
+                "{} in <#{}> (last announced episode: {})",
+                row.title, row.channel_id, row.last_episode
+            )
+        })
+        .collect();
+
+    ctx.say(return_truncated(
+        format!("Airing subscriptions:\n{}", lines.join("\n")),
+        2000,
+    ))
+    .await?;
+
+    Ok(())
+}
@@ -1,477 +1,1770 @@
+use chrono::Datelike;
 use html2text::from_read;
-use poise::serenity_prelude::{AttachmentType, Colour};
-use reqwest::Client;
-use rusted_wumpus_lib::utils::return_truncated;
-use serde_json::json;
-use tracing::instrument;
-use tracing_unwrap::{OptionExt, ResultExt};
+use poise::serenity_prelude::{
+    self as serenity, AttachmentType, ButtonStyle, CollectComponentInteraction, CreateComponents,
+    CreateEmbed, InteractionResponseType,
+};
+use rand::Rng;
+use rusted_wumpus_lib::{
+    anilist::{ListActivity, Media, MediaConnection, MediaFilters, DEFAULT_COLOUR},
+    checks::adult_content_allowed,
+    export::{export_one, ExportFormat},
+    structs::UserRow,
+    utils::return_truncated,
+};
+use tracing::{event, instrument, Level};
 
 use crate::{
-    vars::{ANIME_QUERY, MANGA_QUERY},
+    vars::{
+        ANIME_QUERY, CHARACTER_QUERY, MANGA_QUERY, RANDOM_ANIME_QUERY, RANDOM_MANGA_QUERY,
+        RELATED_QUERY, SEASON_QUERY, STAFF_QUERY, STUDIO_QUERY, TITLE_SEARCH_QUERY,
+        USER_ACTIVITY_QUERY, USER_QUERY,
+    },
     Context, Error,
 };
 
+use super::paginate::{paginate, paginate_lines, PAGINATION_TIMEOUT};
+
+/// How many search candidates to let users page through
+const SEARCH_RESULTS: i32 = 10;
+
+/// Random picks are made from at most this many matches, AniList gets slow on very deep pages
+const RANDOM_MAX_ENTRIES: i32 = 5000;
+
+/// How many pages of 50 the seasonal chart goes through, big seasons have well over a hundred entries
+const SEASON_PAGES: i32 = 3;
+
+/// Marks an autocomplete value as an AniList ID rather than a title to search for
+const ID_PREFIX: &str = "id:";
+
+/// Shown instead of adult entries outside age-restricted channels
+pub(crate) const ADULT_BLOCKED: &str =
+    "This entry is marked as adult content and can only be shown in age-restricted channels";
+
 /// Get an AniList entry for an Anime
 #[instrument]
 #[poise::command(prefix_command, slash_command, category = "Fun")]
 pub async fn anime(
     ctx: Context<'_>,
-    #[description = "Name"] msg: String,
+    #[description = "Name"]
+    #[autocomplete = "autocomplete_anime"]
+    msg: String,
     #[description = "Output long description"] long_desc: Option<bool>,
-    #[description = "Output raw json"] raw: Option<bool>,
+    #[description = "Output the raw AniList data"] raw: Option<bool>,
+    #[description = "Raw output format, JSON by default"] format: Option<ExportFormat>,
 ) -> Result<(), Error> {
     // Tell discord wait longer then 3 seconds
     ctx.defer().await?;
 
-    let client = Client::new();
+    let results = lookup_media(ctx, ANIME_QUERY, &msg).await?;
 
-    // Define query and variables
-    let json = json!({"query": ANIME_QUERY, "variables": {"search": format!("{msg}")}});
+    if results.is_empty() {
+        ctx.say(format!("No anime found for `{msg}`")).await?;
+        return Ok(());
+    }
 
-    // Make HTTP post request
-    let resp = client
-        .post("https://graphql.anilist.co/")
-        .header("Content-Type", "application/json")
-        .header("Accept", "application/json")
-        .body(json.to_string())
-        .send()
-        .await
-        .unwrap_or_log()
-        .text()
+    let allow_adult = adult_content_allowed(ctx).await?;
+
+    if raw.unwrap_or(false) || format.is_some() {
+        if adult_hidden(&results[0], allow_adult) {
+            ctx.say(ADULT_BLOCKED).await?;
+            return Ok(());
+        }
+
+        return send_raw(
+            ctx,
+            ANIME_QUERY,
+            &results[0],
+            "Anime",
+            format.unwrap_or(ExportFormat::Json),
+        )
         .await;
+    }
 
-    // Get json
-    let result: serde_json::Value = serde_json::from_str(&resp.unwrap_or_log()).unwrap_or_log();
+    let want_long = long_desc.unwrap_or(false);
+    paginate_media(
+        ctx,
+        &results,
+        |media| gate_adult(media, allow_adult, |m| anime_embed(m, want_long)),
+        |media| media_links(media, allow_adult),
+    )
+    .await
+}
 
-    let formatted_json = format!("{result:#?}");
+/// Get an AniList entry for a Manga
+#[instrument]
+#[poise::command(prefix_command, slash_command, category = "Fun")]
+pub async fn manga(
+    ctx: Context<'_>,
+    #[description = "Name"]
+    #[autocomplete = "autocomplete_manga"]
+    msg: String,
+    #[description = "Output long description"] long_desc: Option<bool>,
+    #[description = "Output the raw AniList data"] raw: Option<bool>,
+    #[description = "Raw output format, JSON by default"] format: Option<ExportFormat>,
+) -> Result<(), Error> {
+    // Tell discord wait longer then 3 seconds
+    ctx.defer().await?;
 
-    // let anime_id = result["data"]["Media"]["id"].as_u64().unwrap_or_log();
-    let description = from_read(
-        result["data"]["Media"]["description"]
-            .as_str()
-            .unwrap_or_log()
-            .as_bytes(),
-        50,
-    );
-    let status = result["data"]["Media"]["status"].as_str().unwrap_or_log();
-    let anilist_url = result["data"]["Media"]["siteUrl"].as_str().unwrap_or_log();
-    let episode_count = result["data"]["Media"]["episodes"].as_u64().unwrap_or_log();
-    let average_episode_length = result["data"]["Media"]["duration"].as_u64().unwrap_or_log();
-    let average_score = result["data"]["Media"]["averageScore"]
-        .as_u64()
-        .unwrap_or_log();
-    let median_score = result["data"]["Media"]["meanScore"]
-        .as_u64()
-        .unwrap_or_log();
-    let adult = result["data"]["Media"]["isAdult"].as_bool().unwrap_or_log();
-
-    let romaji_title = result["data"]["Media"]["title"]["romaji"]
-        .as_str()
-        .unwrap_or_log();
-    let english_title = if result["data"]["Media"]["title"]["english"]
-        .as_str()
-        .is_some()
-    {
-        result["data"]["Media"]["title"]["english"]
-            .as_str()
-            .unwrap_or_log()
-    } else {
-        result["data"]["Media"]["title"]["romaji"]
-            .as_str()
-            .unwrap_or_log()
-    };
+    let results = lookup_media(ctx, MANGA_QUERY, &msg).await?;
 
-    let base_colour = if result["data"]["Media"]["coverImage"]["color"]
-        .as_str()
-        .is_some()
-    {
-        result["data"]["Media"]["coverImage"]["color"]
-            .as_str()
-            .unwrap_or_log()
-    } else {
-        "#aed6f1"
-    };
+    if results.is_empty() {
+        ctx.say(format!("No manga found for `{msg}`")).await?;
+        return Ok(());
+    }
 
-    let image = result["data"]["Media"]["coverImage"]["extraLarge"]
-        .as_str()
-        .unwrap_or_log();
-    let small_image = result["data"]["Media"]["coverImage"]["large"]
-        .as_str()
-        .unwrap_or_log();
+    let allow_adult = adult_content_allowed(ctx).await?;
 
-    let season = if result["data"]["Media"]["season"].as_str().is_some() {
-        result["data"]["Media"]["season"].as_str().unwrap_or_log()
-    } else {
-        "N/A"
-    };
+    if raw.unwrap_or(false) || format.is_some() {
+        if adult_hidden(&results[0], allow_adult) {
+            ctx.say(ADULT_BLOCKED).await?;
+            return Ok(());
+        }
 
-    let start_year = if result["data"]["Media"]["startDate"]["year"]
-        .as_i64()
-        .is_some()
-    {
-        result["data"]["Media"]["startDate"]["year"]
-            .as_i64()
-            .unwrap_or_log()
-    } else {
-        -1
-    };
-    let start_month = if result["data"]["Media"]["startDate"]["month"]
-        .as_i64()
-        .is_some()
-    {
-        result["data"]["Media"]["startDate"]["month"]
-            .as_i64()
-            .unwrap_or_log()
-    } else {
-        -1
-    };
-    let start_day = if result["data"]["Media"]["startDate"]["day"]
-        .as_i64()
-        .is_some()
-    {
-        result["data"]["Media"]["startDate"]["day"]
-            .as_i64()
-            .unwrap_or_log()
-    } else {
-        -1
-    };
+        return send_raw(
+            ctx,
+            MANGA_QUERY,
+            &results[0],
+            "Manga",
+            format.unwrap_or(ExportFormat::Json),
+        )
+        .await;
+    }
 
-    let end_year = if result["data"]["Media"]["endDate"]["year"]
-        .as_i64()
-        .is_some()
-    {
-        result["data"]["Media"]["endDate"]["year"]
-            .as_i64()
-            .unwrap_or_log()
-    } else {
-        -1
-    };
-    let end_month = if result["data"]["Media"]["endDate"]["month"]
-        .as_i64()
-        .is_some()
+    let want_long = long_desc.unwrap_or(false);
+    paginate_media(
+        ctx,
+        &results,
+        |media| gate_adult(media, allow_adult, |m| manga_embed(m, want_long)),
+        |media| media_links(media, allow_adult),
+    )
+    .await
+}
+
+/// Get an AniList entry for a Character
+#[instrument]
+#[poise::command(prefix_command, slash_command, category = "Fun")]
+pub async fn character(
+    ctx: Context<'_>,
+    #[description = "Name"] msg: String,
+    #[description = "Output long description"] long_desc: Option<bool>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let allow_adult = adult_content_allowed(ctx).await?;
+    let character = if let Some(character) = ctx
+        .data()
+        .anilist
+        .search_character(CHARACTER_QUERY, &msg)
+        .await?
     {
-        result["data"]["Media"]["endDate"]["month"]
-            .as_i64()
-            .unwrap_or_log()
-    } else {
-        -1
-    };
-    let end_day = if result["data"]["Media"]["endDate"]["day"].as_i64().is_some() {
-        result["data"]["Media"]["endDate"]["day"]
-            .as_i64()
-            .unwrap_or_log()
+        character
     } else {
-        -1
+        ctx.say(format!("No character found for `{msg}`")).await?;
+        return Ok(());
     };
 
-    let without_prefix = base_colour.trim_start_matches('#');
-    let colour_i32 = i32::from_str_radix(without_prefix, 16).unwrap_or_log();
+    let field_list = vec![
+        ("Name", or_na(character.name.full.as_ref()), true),
+        ("Native Name", or_na(character.name.native.as_ref()), true),
+        (
+            "Description",
+            describe(character.description.as_deref(), long_desc.unwrap_or(false)),
+            false,
+        ),
+        ("Favourites", or_na(character.favourites), true),
+        (
+            "Top Appearances",
+            appearances(character.media.as_ref(), allow_adult),
+            false,
+        ),
+    ];
+
+    let image = character
+        .image
+        .as_ref()
+        .filter(|_| !top_is_hidden(character.media.as_ref(), allow_adult));
+    let embed = anilist_embed(
+        "Character Result",
+        DEFAULT_COLOUR,
+        character.site_url.as_deref(),
+        image.and_then(|i| i.medium.as_deref()),
+        image.and_then(|i| i.large.as_deref()),
+        field_list,
+    );
+
+    send_embed(ctx, embed).await
+}
+
+/// Get an AniList entry for a Staff member, like a voice actor or director
+#[instrument]
+#[poise::command(prefix_command, slash_command, category = "Fun")]
+pub async fn staff(
+    ctx: Context<'_>,
+    #[description = "Name"] msg: String,
+    #[description = "Output long description"] long_desc: Option<bool>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
 
-    let want_long = if long_desc.is_some() {
-        long_desc.unwrap_or_log()
+    let allow_adult = adult_content_allowed(ctx).await?;
+    let staff = if let Some(staff) = ctx.data().anilist.search_staff(STAFF_QUERY, &msg).await? {
+        staff
     } else {
-        false
+        ctx.say(format!("No staff found for `{msg}`")).await?;
+        return Ok(());
     };
 
-    let field_list = [
-        ("English Name", english_title.to_string(), true),
-        ("Romaji Name", romaji_title.to_string(), true),
-        if want_long {
-            (
-                "Description",
-                return_truncated(description.to_string(), 1024),
-                false,
-            )
-        } else {
-            (
-                "Description",
-                return_truncated(description.to_string(), 512),
-                false,
-            )
-        },
+    let occupations = staff
+        .primary_occupations
+        .as_ref()
+        .filter(|o| !o.is_empty())
+        .map_or_else(|| String::from("N/A"), |o| o.join(", "));
+
+    let field_list = vec![
+        ("Name", or_na(staff.name.full.as_ref()), true),
+        ("Native Name", or_na(staff.name.native.as_ref()), true),
+        ("Occupations", occupations, true),
         (
-            "Start Date",
-            format!("{season} {start_year}/{start_month}/{start_day}"),
-            true,
+            "Description",
+            describe(staff.description.as_deref(), long_desc.unwrap_or(false)),
+            false,
         ),
+        ("Favourites", or_na(staff.favourites), true),
         (
-            "End Date",
-            format!("{end_year}/{end_month}/{end_day}"),
-            true,
+            "Top Works",
+            appearances(staff.staff_media.as_ref(), allow_adult),
+            false,
         ),
-        ("Status", status.to_string(), true),
-        ("Episode Count", format!("{episode_count}"), true),
+    ];
+
+    let image = staff
+        .image
+        .as_ref()
+        .filter(|_| !top_is_hidden(staff.staff_media.as_ref(), allow_adult));
+    let embed = anilist_embed(
+        "Staff Result",
+        DEFAULT_COLOUR,
+        staff.site_url.as_deref(),
+        image.and_then(|i| i.medium.as_deref()),
+        image.and_then(|i| i.large.as_deref()),
+        field_list,
+    );
+
+    send_embed(ctx, embed).await
+}
+
+/// Get an AniList entry for an animation Studio
+#[instrument]
+#[poise::command(prefix_command, slash_command, category = "Fun")]
+pub async fn studio(ctx: Context<'_>, #[description = "Name"] msg: String) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let allow_adult = adult_content_allowed(ctx).await?;
+    let studio = if let Some(studio) = ctx.data().anilist.search_studio(STUDIO_QUERY, &msg).await? {
+        studio
+    } else {
+        ctx.say(format!("No studio found for `{msg}`")).await?;
+        return Ok(());
+    };
+
+    let field_list = vec![
+        ("Name", studio.name.clone(), true),
+        ("Animation Studio?", or_na(studio.is_animation_studio), true),
+        ("Favourites", or_na(studio.favourites), true),
         (
-            "Episode Length",
-            format!("{average_episode_length} minutes"),
-            true,
+            "Top Productions",
+            appearances(studio.media.as_ref(), allow_adult),
+            false,
         ),
-        ("Average score", format!("{average_score}"), true),
-        ("Mean score", format!("{median_score}"), true),
-        ("Is adult?", format!("{adult}"), true),
     ];
 
-    if raw.is_some() {
-        if raw.unwrap_or_log() {
-            ctx.send(|f| {
-                f.content("Anime result")
-                    .ephemeral(false)
-                    .attachment(AttachmentType::Bytes {
-                        data: std::borrow::Cow::Borrowed(formatted_json.as_bytes()),
-                        filename: String::from("Anime.json"),
-                    })
-            })
-            .await?;
-        } else {
-            ctx.send(|f| {
-                f.embed(|b| {
-                    b.colour(Colour::from(colour_i32).tuple())
-                        .description("Anime Result")
-                        .image(image)
-                        .author(|f| f.icon_url(small_image).name("AniList").url(anilist_url))
-                        .fields(field_list)
-                })
-            })
-            .await?;
-        }
-    } else {
-        ctx.send(|f| {
-            f.embed(|b| {
-                b.colour(Colour::from(colour_i32).tuple())
-                    .description("Anime Result")
-                    .image(image)
-                    .author(|f| f.icon_url(small_image).name("AniList").url(anilist_url))
-                    .fields(field_list)
-            })
-        })
-        .await?;
-    }
-    Ok(())
+    // AniList has no studio logos, so borrow the cover of their most popular show
+    let top_show = studio
+        .media
+        .as_ref()
+        .and_then(|m| m.nodes.first())
+        .filter(|m| !adult_hidden(m, allow_adult));
+    let top_cover = top_show.and_then(|m| m.cover_image.as_ref());
+    let colour = top_show.map_or(DEFAULT_COLOUR, Media::colour);
+
+    let embed = anilist_embed(
+        "Studio Result",
+        colour,
+        studio.site_url.as_deref(),
+        top_cover.and_then(|c| c.large.as_deref()),
+        top_cover.and_then(|c| c.extra_large.as_deref()),
+        field_list,
+    );
+
+    send_embed(ctx, embed).await
 }
 
-/// Get an AniList entry for a Manga
+/// Browse the anime airing in a season
 #[instrument]
 #[poise::command(prefix_command, slash_command, category = "Fun")]
-pub async fn manga(
+pub async fn season(
     ctx: Context<'_>,
-    #[description = "Name"] msg: String,
-    #[description = "Output long description"] long_desc: Option<bool>,
-    #[description = "Output raw json"] raw: Option<bool>,
+    #[description = "Season, defaults to the current one"] season: Option<Season>,
+    #[description = "Year, defaults to this year"] year: Option<i32>,
+    #[description = "Sort by"] sort: Option<SeasonSort>,
 ) -> Result<(), Error> {
-    // Tell discord wait longer then 3 seconds
     ctx.defer().await?;
 
-    let client = Client::new();
+    let now = chrono::Utc::now();
+    let season = season.unwrap_or_else(|| Season::from_month(now.month()));
+    let year = year.unwrap_or_else(|| now.year());
+    let sort = sort.unwrap_or(SeasonSort::Popularity);
 
-    // Define query and variables
-    let json = json!({"query": MANGA_QUERY, "variables": {"search": format!("{msg}")}});
+    let results = ctx
+        .data()
+        .anilist
+        .seasonal_media(
+            SEASON_QUERY,
+            season.as_anilist(),
+            year,
+            sort.as_anilist(),
+            SEASON_PAGES,
+        )
+        .await?;
 
-    // Make HTTP post request
-    let resp = client
-        .post("https://graphql.anilist.co/")
-        .header("Content-Type", "application/json")
-        .header("Accept", "application/json")
-        .body(json.to_string())
-        .send()
-        .await
-        .unwrap_or_log()
-        .text()
-        .await;
+    if results.is_empty() {
+        ctx.say(format!("No anime found for {season} {year}"))
+            .await?;
+        return Ok(());
+    }
 
-    // Get json
-    let result: serde_json::Value = serde_json::from_str(&resp.unwrap_or_log()).unwrap_or_log();
+    // Adult entries are left out of the chart rather than blurred, their titles alone can be explicit
+    let allow_adult = adult_content_allowed(ctx).await?;
+    let results: Vec<Media> = results
+        .into_iter()
+        .filter(|media| !adult_hidden(media, allow_adult))
+        .collect();
 
-    let formatted_json = format!("{result:#?}");
+    let lines: Vec<String> = results
+        .iter()
+        .enumerate()
+        .map(|(index, media)| season_line(index + 1, media))
+        .collect();
+    let colour = results.first().map_or(DEFAULT_COLOUR, Media::colour);
 
-    if raw.is_some() && raw.unwrap_or_log() {
-        ctx.send(|f| {
-            f.content("Anime result")
-                .ephemeral(false)
-                .attachment(AttachmentType::Bytes {
-                    data: std::borrow::Cow::Borrowed(formatted_json.as_bytes()),
-                    filename: String::from("Anime.json"),
-                })
-        })
-        .await?;
+    paginate_lines(
+        ctx,
+        &format!("{season} {year} anime by {}", sort.label()),
+        colour,
+        &lines,
+    )
+    .await
+}
 
-        return Ok(());
+/// AniList's `MediaSeason`
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum Season {
+    Winter,
+    Spring,
+    Summer,
+    Fall,
+}
+
+impl Season {
+    /// AniList puts January to March in winter, and so on through the year.
+    fn from_month(month: u32) -> Self {
+        match month {
+            1..=3 => Self::Winter,
+            4..=6 => Self::Spring,
+            7..=9 => Self::Summer,
+            _ => Self::Fall,
+        }
     }
 
-    // let anime_id = result["data"]["Media"]["id"].as_u64().unwrap_or_log();
-    let description = from_read(
-        result["data"]["Media"]["description"]
-            .as_str()
-            .unwrap_or_log()
-            .as_bytes(),
-        50,
-    );
-    let status = result["data"]["Media"]["status"].as_str().unwrap_or_log();
-    let anilist_url = result["data"]["Media"]["siteUrl"].as_str().unwrap_or_log();
-    let volume_count = if result["data"]["Media"]["volumes"].as_i64().is_some() {
-        result["data"]["Media"]["volumes"].as_i64().unwrap_or_log()
-    } else {
-        -1
-    };
-    let chapter_coumt = result["data"]["Media"]["chapters"].as_u64().unwrap_or_log();
-    let average_score = result["data"]["Media"]["averageScore"]
-        .as_u64()
-        .unwrap_or_log();
-    let median_score = result["data"]["Media"]["meanScore"]
-        .as_u64()
-        .unwrap_or_log();
-    let adult = result["data"]["Media"]["isAdult"].as_bool().unwrap_or_log();
-
-    let romaji_title = result["data"]["Media"]["title"]["romaji"]
-        .as_str()
-        .unwrap_or_log();
-    let english_title = if result["data"]["Media"]["title"]["english"]
-        .as_str()
-        .is_some()
-    {
-        result["data"]["Media"]["title"]["english"]
-            .as_str()
-            .unwrap_or_log()
-    } else {
-        result["data"]["Media"]["title"]["romaji"]
-            .as_str()
-            .unwrap_or_log()
-    };
+    const fn as_anilist(self) -> &'static str {
+        match self {
+            Self::Winter => "WINTER",
+            Self::Spring => "SPRING",
+            Self::Summer => "SUMMER",
+            Self::Fall => "FALL",
+        }
+    }
+}
 
-    let base_colour = if result["data"]["Media"]["coverImage"]["color"]
-        .as_str()
-        .is_some()
-    {
-        result["data"]["Media"]["coverImage"]["color"]
-            .as_str()
-            .unwrap_or_log()
-    } else {
-        "#aed6f1"
-    };
+/// Orders offered by the `season` command
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum SeasonSort {
+    Popularity,
+    Score,
+    #[name = "Start Date"]
+    StartDate,
+}
 
-    let image = result["data"]["Media"]["coverImage"]["extraLarge"]
-        .as_str()
-        .unwrap_or_log();
-    let small_image = result["data"]["Media"]["coverImage"]["large"]
-        .as_str()
-        .unwrap_or_log();
+impl SeasonSort {
+    const fn as_anilist(self) -> &'static str {
+        match self {
+            Self::Popularity => "POPULARITY_DESC",
+            Self::Score => "SCORE_DESC",
+            Self::StartDate => "START_DATE",
+        }
+    }
 
-    let season = if result["data"]["Media"]["season"].as_str().is_some() {
-        result["data"]["Media"]["season"].as_str().unwrap_or_log()
-    } else {
-        "N/A"
-    };
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Popularity => "popularity",
+            Self::Score => "score",
+            Self::StartDate => "start date",
+        }
+    }
+}
 
-    let start_year = if result["data"]["Media"]["startDate"]["year"]
-        .as_i64()
-        .is_some()
-    {
-        result["data"]["Media"]["startDate"]["year"]
-            .as_i64()
-            .unwrap_or_log()
-    } else {
-        -1
-    };
-    let start_month = if result["data"]["Media"]["startDate"]["month"]
-        .as_i64()
-        .is_some()
-    {
-        result["data"]["Media"]["startDate"]["month"]
-            .as_i64()
-            .unwrap_or_log()
-    } else {
-        -1
+/// One line of the seasonal chart, like "**1.** [Title](url) · TV · 12 episodes · 85% · 2026/1/5".
+fn season_line(rank: usize, media: &Media) -> String {
+    let title = match media.site_url.as_deref() {
+        Some(url) => format!("[{}]({url})", media.display_title()),
+        None => media.display_title().to_string(),
     };
-    let start_day = if result["data"]["Media"]["startDate"]["day"]
-        .as_i64()
-        .is_some()
+
+    let mut details = vec![media.format.clone().unwrap_or_else(|| String::from("?"))];
+    if let Some(episodes) = media.episodes {
+        details.push(format!("{episodes} episodes"));
+    }
+    if let Some(score) = media.average_score {
+        details.push(format!("{score}%"));
+    }
+    if let Some(start_date) = &media.start_date {
+        details.push(start_date.to_string());
+    }
+
+    format!("**{rank}.** {title} · {}", details.join(" · "))
+}
+
+/// Explore an entry's sequels, prequels, adaptations and recommendations
+#[instrument]
+#[poise::command(prefix_command, slash_command, category = "Fun")]
+pub async fn related(
+    ctx: Context<'_>,
+    #[description = "Anime or manga"] kind: MediaKind,
+    #[description = "Name"] msg: String,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let anilist = &ctx.data().anilist;
+    let id = msg
+        .strip_prefix(ID_PREFIX)
+        .and_then(|id| id.parse::<i64>().ok());
+
+    let media = if let Some(media) = anilist
+        .media_of_type(RELATED_QUERY, id, Some(msg.as_str()), kind.as_anilist())
+        .await?
     {
-        result["data"]["Media"]["startDate"]["day"]
-            .as_i64()
-            .unwrap_or_log()
+        media
     } else {
-        -1
+        ctx.say(format!("No {} found for `{msg}`", kind.label()))
+            .await?;
+        return Ok(());
     };
 
-    let end_year = if result["data"]["Media"]["endDate"]["year"]
-        .as_i64()
-        .is_some()
-    {
-        result["data"]["Media"]["endDate"]["year"]
-            .as_i64()
-            .unwrap_or_log()
-    } else {
-        -1
+    let allow_adult = adult_content_allowed(ctx).await?;
+    if adult_hidden(&media, allow_adult) {
+        ctx.say(ADULT_BLOCKED).await?;
+        return Ok(());
+    }
+
+    let entries = related_entries(&media, allow_adult);
+    if entries.is_empty() {
+        ctx.say(format!(
+            "No related entries found for {}",
+            media.display_title()
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    let ctx_id = ctx.id();
+    let menu_id = format!("{ctx_id}related");
+    let back_id = format!("{ctx_id}back");
+
+    let components = |show_back: bool| {
+        let mut components = CreateComponents::default();
+        components.create_action_row(|r| {
+            r.create_select_menu(|m| {
+                m.custom_id(&menu_id)
+                    .placeholder("Open an entry")
+                    .options(|o| {
+                        for (index, entry) in entries.iter().enumerate() {
+                            o.create_option(|opt| {
+                                opt.label(return_truncated(
+                                    entry.media.display_title().to_string(),
+                                    100,
+                                ))
+                                .value(index)
+                                .description(&entry.relation)
+                            });
+                        }
+                        o
+                    })
+            })
+        });
+
+        if show_back {
+            components.create_action_row(|r| {
+                r.create_button(|b| {
+                    b.custom_id(&back_id)
+                        .label("Back")
+                        .style(ButtonStyle::Secondary)
+                })
+            });
+        }
+
+        components
     };
-    let end_month = if result["data"]["Media"]["endDate"]["month"]
-        .as_i64()
-        .is_some()
+
+    let overview = related_overview(&media, &entries);
+    let mut current = overview.clone();
+    let first_components = components(false);
+    let reply = ctx
+        .send(|f| {
+            f.embed(|b| {
+                *b = overview.clone();
+                b
+            })
+            .components(|c| {
+                *c = first_components;
+                c
+            })
+        })
+        .await?;
+
+    while let Some(press) = CollectComponentInteraction::new(ctx)
+        .author_id(ctx.author().id)
+        .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
+        .timeout(PAGINATION_TIMEOUT)
+        .await
     {
-        result["data"]["Media"]["endDate"]["month"]
-            .as_i64()
-            .unwrap_or_log()
-    } else {
-        -1
-    };
-    let end_day = if result["data"]["Media"]["endDate"]["day"].as_i64().is_some() {
-        result["data"]["Media"]["endDate"]["day"]
-            .as_i64()
-            .unwrap_or_log()
-    } else {
-        -1
-    };
+        let (embed, show_back) = if press.data.custom_id == back_id {
+            (overview.clone(), false)
+        } else if press.data.custom_id == menu_id {
+            let entry = press
+                .data
+                .values
+                .first()
+                .and_then(|value| value.parse::<usize>().ok())
+                .and_then(|index| entries.get(index));
 
-    let without_prefix = base_colour.trim_start_matches('#');
-    let colour_i32 = i32::from_str_radix(without_prefix, 16).unwrap_or_log();
+            let entry = if let Some(entry) = entry {
+                entry
+            } else {
+                continue;
+            };
 
-    let want_long = if long_desc.is_some() {
-        long_desc.unwrap_or_log()
-    } else {
-        false
-    };
+            // The full entry can take a moment to fetch, so acknowledge the choice first
+            press.defer(ctx).await?;
+            let embed = related_embed(ctx, entry.media, allow_adult).await?;
 
-    let field_list = [
-        ("English Name", english_title.to_string(), true),
-        ("Romaji Name", romaji_title.to_string(), true),
-        if want_long {
-            (
-                "Description",
-                return_truncated(description.to_string(), 1024),
-                false,
-            )
+            press
+                .edit_original_interaction_response(ctx, |r| {
+                    r.set_embed(embed.clone()).set_components(components(true))
+                })
+                .await?;
+
+            current = embed;
+            continue;
         } else {
-            (
-                "Description",
-                return_truncated(description.to_string(), 512),
-                false,
-            )
-        },
-        (
-            "Start Date",
-            format!("{season} {start_year}/{start_month}/{start_day}"),
-            true,
-        ),
-        (
-            "End Date",
-            format!("{end_year}/{end_month}/{end_day}"),
-            true,
-        ),
-        ("Status", status.to_string(), true),
-        ("Volume Count", format!("{volume_count}"), true),
-        ("Chapter Count", format!("{chapter_coumt} minutes"), true),
-        ("Average Score", format!("{average_score}"), true),
-        ("Mean Score", format!("{median_score}"), true),
-        ("Is Adult?", format!("{adult}"), true),
-    ];
+            continue;
+        };
 
-    ctx.send(|f| {
-        f.embed(|b| {
-            b.colour(Colour::from(colour_i32).tuple())
-                .description("Anime Result")
-                .image(image)
-                .author(|f| f.icon_url(small_image).name("AniList").url(anilist_url))
-                .fields(field_list)
+        press
+            .create_interaction_response(ctx, |r| {
+                r.kind(InteractionResponseType::UpdateMessage)
+                    .interaction_response_data(|d| {
+                        d.set_embed(embed.clone())
+                            .set_components(components(show_back))
+                    })
+            })
+            .await?;
+
+        current = embed;
+    }
+
+    // Leave whatever was open up without the dead menu
+    reply
+        .edit(ctx, |f| {
+            f.embed(|b| {
+                *b = current;
+                b
+            })
+            .components(|c| c)
         })
-    })
-    .await?;
+        .await?;
+
     Ok(())
 }
+
+/// AniList's `MediaType`
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum MediaKind {
+    Anime,
+    Manga,
+}
+
+impl MediaKind {
+    pub(crate) const fn as_anilist(self) -> &'static str {
+        match self {
+            Self::Anime => "ANIME",
+            Self::Manga => "MANGA",
+        }
+    }
+
+    pub(crate) const fn label(self) -> &'static str {
+        match self {
+            Self::Anime => "anime",
+            Self::Manga => "manga",
+        }
+    }
+}
+
+/// An entry in the `related` explorer and how it's related to the searched one.
+struct RelatedEntry<'a> {
+    relation: String,
+    media: &'a Media,
+}
+
+/// Relations first, then recommendations, capped at the 25 options a select menu can hold.
+fn related_entries(media: &Media, allow_adult: bool) -> Vec<RelatedEntry<'_>> {
+    let relations = media
+        .relations
+        .as_ref()
+        .map(|r| r.edges.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|edge| {
+            edge.node.as_ref().map(|node| RelatedEntry {
+                relation: relation_name(edge.relation_type.as_deref()),
+                media: node,
+            })
+        });
+
+    let recommendations = media
+        .recommendations
+        .as_ref()
+        .map(|r| r.nodes.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|rec| {
+            rec.media_recommendation.as_ref().map(|node| RelatedEntry {
+                relation: String::from("Recommended"),
+                media: node,
+            })
+        });
+
+    relations
+        .chain(recommendations)
+        .filter(|entry| !adult_hidden(entry.media, allow_adult))
+        .take(25)
+        .collect()
+}
+
+/// Turns AniList's `SIDE_STORY` style relation types into "Side Story".
+fn relation_name(relation_type: Option<&str>) -> String {
+    relation_type
+        .unwrap_or("OTHER")
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_string() + &chars.as_str().to_lowercase()
+            })
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The list shown before anything is chosen.
+fn related_overview(media: &Media, entries: &[RelatedEntry<'_>]) -> CreateEmbed {
+    let lines: Vec<String> = entries
+        .iter()
+        .map(|entry| {
+            let title = match entry.media.site_url.as_deref() {
+                Some(url) => format!("[{}]({url})", entry.media.display_title()),
+                None => entry.media.display_title().to_string(),
+            };
+            let format = entry.media.format.as_deref().unwrap_or("?");
+
+            format!("**{}** · {title} ({format})", entry.relation)
+        })
+        .collect();
+
+    let mut embed = CreateEmbed::default();
+    embed
+        .title(format!("Related to {}", media.display_title()))
+        .colour(media.colour())
+        .description(return_truncated(lines.join("\n"), 4096));
+
+    if let Some(url) = media.site_url.as_deref() {
+        embed.url(url);
+    }
+    if let Some(cover) = media.cover_image.as_ref().and_then(|c| c.large.as_deref()) {
+        embed.thumbnail(cover);
+    }
+
+    embed
+}
+
+/// Fetches the full entry for a related `media` and builds its usual `anime`/`manga` embed.
+async fn related_embed(
+    ctx: Context<'_>,
+    media: &Media,
+    allow_adult: bool,
+) -> Result<CreateEmbed, Error> {
+    let is_manga = media.media_type.as_deref() == Some("MANGA");
+    let query = if is_manga { MANGA_QUERY } else { ANIME_QUERY };
+
+    let full = if let Some(full) = ctx.data().anilist.media_by_id(query, media.id).await? {
+        full
+    } else {
+        return Ok(gate_adult(media, allow_adult, |m| {
+            media_embed(m, "Related Result", Vec::new())
+        }));
+    };
+
+    Ok(gate_adult(&full, allow_adult, |m| {
+        if is_manga {
+            manga_embed(m, false)
+        } else {
+            anime_embed(m, false)
+        }
+    }))
+}
+
+/// Compare two anime or manga side by side
+#[instrument]
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Fun",
+    subcommands("compare_anime", "compare_manga")
+)]
+pub async fn compare(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say("Use `compare anime` or `compare manga`").await?;
+
+    Ok(())
+}
+
+/// Compare two anime side by side
+#[instrument]
+#[poise::command(prefix_command, slash_command, rename = "anime", category = "Fun")]
+pub async fn compare_anime(
+    ctx: Context<'_>,
+    #[description = "First title"]
+    #[autocomplete = "autocomplete_anime"]
+    a: String,
+    #[description = "Second title"]
+    #[autocomplete = "autocomplete_anime"]
+    b: String,
+) -> Result<(), Error> {
+    compare_media(ctx, MediaKind::Anime, &a, &b).await
+}
+
+/// Compare two manga side by side
+#[instrument]
+#[poise::command(prefix_command, slash_command, rename = "manga", category = "Fun")]
+pub async fn compare_manga(
+    ctx: Context<'_>,
+    #[description = "First title"]
+    #[autocomplete = "autocomplete_manga"]
+    a: String,
+    #[description = "Second title"]
+    #[autocomplete = "autocomplete_manga"]
+    b: String,
+) -> Result<(), Error> {
+    compare_media(ctx, MediaKind::Manga, &a, &b).await
+}
+
+/// Looks up `a` and `b` as `kind` and sends them side by side.
+async fn compare_media(ctx: Context<'_>, kind: MediaKind, a: &str, b: &str) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let query = match kind {
+        MediaKind::Anime => ANIME_QUERY,
+        MediaKind::Manga => MANGA_QUERY,
+    };
+
+    let mut entries = Vec::new();
+    for msg in [a, b] {
+        if let Some(media) = lookup_media(ctx, query, msg).await?.into_iter().next() {
+            entries.push(media);
+        } else {
+            ctx.say(format!("No {} found for `{msg}`", kind.label()))
+                .await?;
+            return Ok(());
+        }
+    }
+
+    if entries.iter().any(|m| m.is_adult == Some(true)) && !adult_content_allowed(ctx).await? {
+        ctx.say(ADULT_BLOCKED).await?;
+        return Ok(());
+    }
+
+    send_embed(ctx, compare_embed(&entries[0], &entries[1], kind)).await
+}
+
+/// Lines up the date, count and score fields of `a` and `b`, with the higher score in bold.
+fn compare_embed(a: &Media, b: &Media, kind: MediaKind) -> CreateEmbed {
+    let name_a = return_truncated(a.display_title().to_string(), 40);
+    let name_b = return_truncated(b.display_title().to_string(), 40);
+
+    let comparison_fields = |media: &Media| {
+        let mut field_list = date_fields(media);
+        field_list.extend(stat_fields(media, kind));
+        field_list.push(("Genres", genres(media), false));
+        field_list
+    };
+
+    let field_list: Vec<(&'static str, String, bool)> = comparison_fields(a)
+        .into_iter()
+        .zip(comparison_fields(b))
+        .map(|((name, value_a, inline), (_, value_b, _))| {
+            let (value_a, value_b) = if matches!(name, "Average Score" | "Mean Score") {
+                highlight_higher(value_a, value_b)
+            } else {
+                (value_a, value_b)
+            };
+
+            (
+                name,
+                format!("{name_a}: {value_a}\n{name_b}: {value_b}"),
+                inline,
+            )
+        })
+        .collect();
+
+    let winner = match (a.average_score, b.average_score) {
+        (Some(score_a), Some(score_b)) if score_a > score_b => Some(a),
+        (Some(score_a), Some(score_b)) if score_b > score_a => Some(b),
+        _ => None,
+    };
+    let description = match winner {
+        Some(winner) => format!("{} is rated higher", winner.display_title()),
+        None if a.average_score.is_some() && b.average_score.is_some() => {
+            String::from("Both are rated the same")
+        }
+        None => String::from("Not enough scores to pick a favourite"),
+    };
+
+    let shown = winner.unwrap_or(a);
+    let mut embed = CreateEmbed::default();
+    embed
+        .title(format!("{name_a} vs {name_b}"))
+        .colour(shown.colour())
+        .description(description)
+        .fields(field_list);
+
+    if let Some(cover) = shown.cover_image.as_ref().and_then(|c| c.large.as_deref()) {
+        embed.thumbnail(cover);
+    }
+
+    embed
+}
+
+/// Bolds whichever of two numeric field values is higher, leaving ties and "N/A" alone.
+fn highlight_higher(a: String, b: String) -> (String, String) {
+    match (a.parse::<i32>(), b.parse::<i32>()) {
+        (Ok(x), Ok(y)) if x > y => (format!("**{a}**"), b),
+        (Ok(x), Ok(y)) if y > x => (a, format!("**{b}**")),
+        _ => (a, b),
+    }
+}
+
+fn genres(media: &Media) -> String {
+    match media.genres.as_deref() {
+        Some(genres) if !genres.is_empty() => genres.join(", "),
+        _ => String::from("N/A"),
+    }
+}
+
+/// Get a random anime, optionally filtered
+#[instrument]
+#[poise::command(prefix_command, slash_command, category = "Fun")]
+pub async fn randomanime(
+    ctx: Context<'_>,
+    #[description = "Genres, comma separated, any of them match"] genres: Option<String>,
+    #[description = "Minimum average score"]
+    #[min = 0]
+    #[max = 100]
+    min_score: Option<i32>,
+    #[description = "Format"] format: Option<AnimeFormat>,
+    #[description = "Earliest start year"] year_from: Option<i32>,
+    #[description = "Latest start year"] year_to: Option<i32>,
+    #[description = "Leave out adult entries, on by default"] exclude_adult: Option<bool>,
+) -> Result<(), Error> {
+    let format = format.map(AnimeFormat::as_anilist);
+    let filters = media_filters(genres, min_score, format, year_from, year_to, exclude_adult);
+    random_media(ctx, MediaKind::Anime, filters).await
+}
+
+/// Get a random manga, optionally filtered
+#[instrument]
+#[poise::command(prefix_command, slash_command, category = "Fun")]
+pub async fn randommanga(
+    ctx: Context<'_>,
+    #[description = "Genres, comma separated, any of them match"] genres: Option<String>,
+    #[description = "Minimum average score"]
+    #[min = 0]
+    #[max = 100]
+    min_score: Option<i32>,
+    #[description = "Format"] format: Option<MangaFormat>,
+    #[description = "Earliest start year"] year_from: Option<i32>,
+    #[description = "Latest start year"] year_to: Option<i32>,
+    #[description = "Leave out adult entries, on by default"] exclude_adult: Option<bool>,
+) -> Result<(), Error> {
+    let format = format.map(MangaFormat::as_anilist);
+    let filters = media_filters(genres, min_score, format, year_from, year_to, exclude_adult);
+    random_media(ctx, MediaKind::Manga, filters).await
+}
+
+/// AniList's anime `MediaFormat`s
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum AnimeFormat {
+    #[name = "TV"]
+    Tv,
+    #[name = "TV Short"]
+    TvShort,
+    Movie,
+    Special,
+    #[name = "OVA"]
+    Ova,
+    #[name = "ONA"]
+    Ona,
+    Music,
+}
+
+impl AnimeFormat {
+    const fn as_anilist(self) -> &'static str {
+        match self {
+            Self::Tv => "TV",
+            Self::TvShort => "TV_SHORT",
+            Self::Movie => "MOVIE",
+            Self::Special => "SPECIAL",
+            Self::Ova => "OVA",
+            Self::Ona => "ONA",
+            Self::Music => "MUSIC",
+        }
+    }
+}
+
+/// AniList's manga `MediaFormat`s
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum MangaFormat {
+    Manga,
+    #[name = "Light Novel"]
+    Novel,
+    #[name = "One Shot"]
+    OneShot,
+}
+
+impl MangaFormat {
+    const fn as_anilist(self) -> &'static str {
+        match self {
+            Self::Manga => "MANGA",
+            Self::Novel => "NOVEL",
+            Self::OneShot => "ONE_SHOT",
+        }
+    }
+}
+
+fn media_filters(
+    genres: Option<String>,
+    min_score: Option<i32>,
+    format: Option<&'static str>,
+    year_from: Option<i32>,
+    year_to: Option<i32>,
+    exclude_adult: Option<bool>,
+) -> MediaFilters {
+    MediaFilters {
+        genres: genres
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|genre| !genre.is_empty())
+            .map(String::from)
+            .collect(),
+        min_score,
+        format: format.map(String::from),
+        year_from,
+        year_to,
+        exclude_adult: exclude_adult.unwrap_or(true),
+    }
+}
+
+/// Picks a random entry matching `filters` and shows it in the usual embed.
+///
+/// The first request only finds out how many entries match, the second fetches the one picked.
+async fn random_media(
+    ctx: Context<'_>,
+    kind: MediaKind,
+    mut filters: MediaFilters,
+) -> Result<(), Error> {
+    if let (Some(from), Some(to)) = (filters.year_from, filters.year_to) {
+        if from > to {
+            ctx.say("The earliest start year can't be after the latest one")
+                .await?;
+            return Ok(());
+        }
+    }
+
+    ctx.defer().await?;
+
+    let allow_adult = adult_content_allowed(ctx).await?;
+    if !allow_adult {
+        filters.exclude_adult = true;
+    }
+
+    let query = match kind {
+        MediaKind::Anime => RANDOM_ANIME_QUERY,
+        MediaKind::Manga => RANDOM_MANGA_QUERY,
+    };
+    let anilist = &ctx.data().anilist;
+
+    let total = anilist
+        .filtered_media(query, &filters, 1, 1)
+        .await?
+        .page_info
+        .and_then(|p| p.total)
+        .unwrap_or(0)
+        .min(RANDOM_MAX_ENTRIES);
+
+    if total < 1 {
+        ctx.say(format!("No {} matches those filters", kind.label()))
+            .await?;
+        return Ok(());
+    }
+
+    let page = rand::thread_rng().gen_range(1..=total);
+    let media = if let Some(media) = anilist
+        .filtered_media(query, &filters, page, 1)
+        .await?
+        .media
+        .into_iter()
+        .next()
+    {
+        media
+    } else {
+        ctx.say(format!("No {} matches those filters", kind.label()))
+            .await?;
+        return Ok(());
+    };
+
+    let embed = gate_adult(&media, allow_adult, |m| match kind {
+        MediaKind::Anime => anime_embed(m, false),
+        MediaKind::Manga => manga_embed(m, false),
+    });
+    let mut components = CreateComponents::default();
+    add_link_buttons(&mut components, &media_links(&media, allow_adult));
+
+    ctx.send(|f| {
+        f.embed(|b| {
+            *b = embed;
+            b
+        })
+        .components(|c| {
+            *c = components;
+            c
+        })
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// AniList account commands
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Fun",
+    subcommands("anilist_user", "anilist_link", "anilist_unlink")
+)]
+pub async fn anilist(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say("Use a subcommand, like `anilist user <name>` or `anilist link <name>`")
+        .await?;
+
+    Ok(())
+}
+
+/// Show an AniList user's profile and list statistics
+///
+/// Leave the name out (or use "me") for your own linked account, or pick a Discord user to see theirs.
+#[instrument]
+#[poise::command(prefix_command, slash_command, rename = "user", category = "Fun")]
+pub async fn anilist_user(
+    ctx: Context<'_>,
+    #[description = "AniList username, defaults to your linked account"] name: Option<String>,
+    #[description = "Discord user whose linked account to show"] member: Option<serenity::User>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let name = if let Some(name) = resolve_anilist_name(ctx, name, member.as_ref()).await? {
+        name
+    } else {
+        ctx.say("No AniList account linked, use `anilist link <name>` first or give a username")
+            .await?;
+        return Ok(());
+    };
+
+    let anilist = &ctx.data().anilist;
+
+    let user = if let Some(user) = anilist.user(USER_QUERY, &name).await? {
+        user
+    } else {
+        ctx.say(format!("No AniList user called `{name}`")).await?;
+        return Ok(());
+    };
+
+    let activity = anilist
+        .recent_activity(USER_ACTIVITY_QUERY, user.id)
+        .await?;
+
+    let stats = user.statistics.as_ref();
+    let anime = stats.and_then(|s| s.anime.as_ref());
+    let manga = stats.and_then(|s| s.manga.as_ref());
+
+    let favourite_genres = anime
+        .map(|a| a.genres.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|g| g.genre.as_deref())
+        .collect::<Vec<_>>();
+
+    let field_list = vec![
+        ("Anime Count", or_na(anime.and_then(|a| a.count)), true),
+        (
+            "Anime Mean Score",
+            or_na(anime.and_then(|a| a.mean_score)),
+            true,
+        ),
+        (
+            "Time Watched",
+            anime
+                .and_then(|a| a.minutes_watched)
+                .map_or_else(|| String::from("N/A"), format_minutes),
+            true,
+        ),
+        ("Manga Count", or_na(manga.and_then(|m| m.count)), true),
+        (
+            "Manga Mean Score",
+            or_na(manga.and_then(|m| m.mean_score)),
+            true,
+        ),
+        (
+            "Chapters Read",
+            or_na(manga.and_then(|m| m.chapters_read)),
+            true,
+        ),
+        (
+            "Favourite Genres",
+            if favourite_genres.is_empty() {
+                String::from("N/A")
+            } else {
+                favourite_genres.join(", ")
+            },
+            false,
+        ),
+        ("Recent Activity", describe_activity(&activity), false),
+    ];
+
+    let avatar = user.avatar.as_ref();
+    let mut embed = anilist_embed(
+        "User Result",
+        user.colour(),
+        user.site_url.as_deref(),
+        avatar.and_then(|a| a.medium.as_deref()),
+        None,
+        field_list,
+    );
+    embed.title(&user.name);
+    if let Some(avatar) = avatar.and_then(|a| a.large.as_deref()) {
+        embed.thumbnail(avatar);
+    }
+
+    send_embed(ctx, embed).await
+}
+
+/// Link your Discord account to an AniList username
+#[instrument]
+#[poise::command(prefix_command, slash_command, rename = "link", category = "Fun")]
+pub async fn anilist_link(
+    ctx: Context<'_>,
+    #[description = "AniList username"] name: String,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    // Make sure the account exists and store AniList's capitalisation of the name
+    let user = if let Some(user) = ctx.data().anilist.user(USER_QUERY, name.trim()).await? {
+        user
+    } else {
+        ctx.say(format!("No AniList user called `{name}`")).await?;
+        return Ok(());
+    };
+
+    let row: UserRow =
+        sqlx::query_as("UPDATE users SET anilist_username = ($1) WHERE (id) = ($2) RETURNING *;")
+            .bind(&user.name)
+            .bind(ctx.author().id.0.to_string())
+            .fetch_one(&ctx.data().db.clone())
+            .await?;
+
+    ctx.say(format!(
+        "Linked your account to AniList user {}",
+        row.anilist_username.unwrap_or(user.name)
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Remove the AniList username linked to your Discord account
+#[instrument]
+#[poise::command(prefix_command, slash_command, rename = "unlink", category = "Fun")]
+pub async fn anilist_unlink(ctx: Context<'_>) -> Result<(), Error> {
+    let pool = ctx.data().db.clone();
+    let author_id = ctx.author().id.0.to_string();
+
+    let row: Option<UserRow> = sqlx::query_as("SELECT * FROM users WHERE (id) = ($1) LIMIT 1;")
+        .bind(&author_id)
+        .fetch_optional(&pool)
+        .await?;
+
+    if let Some(name) = row.and_then(|r| r.anilist_username) {
+        sqlx::query("UPDATE users SET anilist_username = NULL WHERE (id) = ($1);")
+            .bind(&author_id)
+            .execute(&pool)
+            .await?;
+
+        ctx.say(format!("Unlinked AniList user {name}")).await?;
+    } else {
+        ctx.say("You don't have an AniList account linked").await?;
+    }
+
+    Ok(())
+}
+
+/// Works out which AniList account a command is about.
+///
+/// An explicit name wins, "me" or no name at all means the author's linked account, and a Discord user means theirs.
+pub async fn resolve_anilist_name(
+    ctx: Context<'_>,
+    name: Option<String>,
+    member: Option<&serenity::User>,
+) -> Result<Option<String>, Error> {
+    match name.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() && !name.eq_ignore_ascii_case("me") => {
+            return Ok(Some(name.to_string()))
+        }
+        _ => {}
+    }
+
+    let user = member.unwrap_or_else(|| ctx.author());
+
+    let row: Option<UserRow> = sqlx::query_as("SELECT * FROM users WHERE (id) = ($1) LIMIT 1;")
+        .bind(user.id.0.to_string())
+        .fetch_optional(&ctx.data().db.clone())
+        .await?;
+
+    Ok(row.and_then(|r| r.anilist_username))
+}
+
+/// Turns a minute count into something like "12 days, 3 hours".
+fn format_minutes(minutes: i32) -> String {
+    let hours = minutes / 60;
+    let (days, hours) = (hours / 24, hours % 24);
+
+    if days > 0 {
+        format!("{days} days, {hours} hours")
+    } else {
+        format!("{hours} hours, {} minutes", minutes % 60)
+    }
+}
+
+/// Lists list activity as "Watched episode 5 of [Title](url), 2 hours ago" lines.
+fn describe_activity(activity: &[ListActivity]) -> String {
+    let lines: Vec<String> = activity
+        .iter()
+        .filter_map(|a| {
+            let media = a.media.as_ref()?;
+            let status = a.status.as_deref().unwrap_or("updated");
+
+            let title = match media.site_url.as_deref() {
+                Some(url) => format!("[{}]({url})", media.display_title()),
+                None => media.display_title().to_string(),
+            };
+
+            let mut line = match a.progress.as_deref() {
+                Some(progress) => format!("{status} {progress} of {title}"),
+                None => format!("{status} {title}"),
+            };
+            if let Some(created_at) = a.created_at {
+                line.push_str(&format!(", <t:{created_at}:R>"));
+            }
+
+            // AniList statuses are lower case, e.g. "watched episode"
+            let mut chars = line.chars();
+            Some(chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars).collect()
+            }))
+        })
+        .collect();
+
+    if lines.is_empty() {
+        String::from("N/A")
+    } else {
+        return_truncated(lines.join("\n"), 1024)
+    }
+}
+
+/// Suggests anime titles while the user is typing
+pub(crate) async fn autocomplete_anime(
+    ctx: Context<'_>,
+    partial: &str,
+) -> impl Iterator<Item = poise::AutocompleteChoice<String>> {
+    autocomplete_titles(ctx, partial, "ANIME").await.into_iter()
+}
+
+/// Suggests manga titles while the user is typing
+async fn autocomplete_manga(
+    ctx: Context<'_>,
+    partial: &str,
+) -> impl Iterator<Item = poise::AutocompleteChoice<String>> {
+    autocomplete_titles(ctx, partial, "MANGA").await.into_iter()
+}
+
+/// Looks up title suggestions for `partial` once the user stops typing.
+///
+/// The choice value is the AniList ID prefixed with [`ID_PREFIX`] so the command can fetch that exact entry.
+async fn autocomplete_titles(
+    ctx: Context<'_>,
+    partial: &str,
+    media_type: &str,
+) -> Vec<poise::AutocompleteChoice<String>> {
+    if partial.trim().is_empty() {
+        return Vec::new();
+    }
+
+    // Only the last keystroke in a burst is worth searching for, repeats come from the AniList cache
+    if !ctx.data().autocomplete.debounce(ctx.author().id.0).await {
+        return Vec::new();
+    }
+
+    let results = ctx
+        .data()
+        .anilist
+        .search_titles(TITLE_SEARCH_QUERY, partial, media_type)
+        .await;
+
+    // Titles alone can be explicit, so adult entries aren't suggested where they couldn't be shown
+    let allow_adult = adult_content_allowed(ctx).await.unwrap_or(false);
+    let suggestions: Vec<Media> = match results {
+        Ok(results) => results
+            .into_iter()
+            .filter(|media| !adult_hidden(media, allow_adult))
+            .collect(),
+        Err(why) => {
+            event!(Level::WARN, "AniList autocomplete failed." = %why);
+            return Vec::new();
+        }
+    };
+
+    suggestions
+        .iter()
+        .map(|media| poise::AutocompleteChoice {
+            name: suggestion_title(media),
+            value: format!("{ID_PREFIX}{}", media.id),
+        })
+        .collect()
+}
+
+/// Romaji title with the English one alongside when it differs, cut down to Discord's 100 character choice limit.
+fn suggestion_title(media: &Media) -> String {
+    let romaji = media.title.romaji.as_deref().unwrap_or("Unknown");
+
+    let title = match media.title.english.as_deref() {
+        Some(english) if english != romaji => format!("{romaji} ({english})"),
+        _ => romaji.to_string(),
+    };
+
+    return_truncated(title, 100)
+}
+
+/// Fetches the exact entry when `msg` came from autocomplete, otherwise searches AniList for it.
+pub(crate) async fn lookup_media(
+    ctx: Context<'_>,
+    query: &str,
+    msg: &str,
+) -> Result<Vec<Media>, Error> {
+    let anilist = &ctx.data().anilist;
+
+    if let Some(id) = msg
+        .strip_prefix(ID_PREFIX)
+        .and_then(|id| id.parse::<i64>().ok())
+    {
+        return Ok(anilist.media_by_id(query, id).await?.into_iter().collect());
+    }
+
+    anilist.search_media_page(query, msg, SEARCH_RESULTS).await
+}
+
+/// Builds the embed for `media`, or a placeholder without the cover or description if it's adult content that can't be shown here.
+///
+/// `allow_adult` comes from [`adult_content_allowed`].
+pub(crate) fn gate_adult(
+    media: &Media,
+    allow_adult: bool,
+    build_embed: impl Fn(&Media) -> CreateEmbed,
+) -> CreateEmbed {
+    if !adult_hidden(media, allow_adult) {
+        return build_embed(media);
+    }
+
+    anilist_embed(
+        ADULT_BLOCKED,
+        DEFAULT_COLOUR,
+        media.site_url.as_deref(),
+        None,
+        None,
+        vec![
+            ("Name", format!("||{}||", media.display_title()), true),
+            ("Is Adult?", String::from("true"), true),
+        ],
+    )
+}
+
+/// Builds the embed for an anime result.
+fn anime_embed(media: &Media, want_long: bool) -> CreateEmbed {
+    let mut field_list = common_fields(media, want_long);
+    field_list.extend(stat_fields(media, MediaKind::Anime));
+    field_list.extend([
+        ("Is Adult?", or_na(media.is_adult), true),
+        ("Hashtag", or_na(media.hashtag.as_ref()), true),
+        ("Latest Episodes", streaming_episodes(media), false),
+    ]);
+
+    media_embed(media, "Anime Result", field_list)
+}
+
+/// Links to the last few episodes on streaming sites, AniList lists them oldest first.
+fn streaming_episodes(media: &Media) -> String {
+    let episodes = media.streaming_episodes.as_deref().unwrap_or_default();
+
+    let lines: Vec<String> = episodes
+        .iter()
+        .rev()
+        .take(3)
+        .filter_map(|episode| {
+            let title = episode.title.as_deref().unwrap_or("Episode");
+            let site = episode.site.as_deref().unwrap_or("?");
+            episode
+                .url
+                .as_deref()
+                .map(|url| format!("[{title}]({url}) ({site})"))
+        })
+        .collect();
+
+    if lines.is_empty() {
+        String::from("N/A")
+    } else {
+        return_truncated(lines.join("\n"), 1024)
+    }
+}
+
+/// Builds the embed for a manga result.
+fn manga_embed(media: &Media, want_long: bool) -> CreateEmbed {
+    let mut field_list = common_fields(media, want_long);
+    field_list.extend(stat_fields(media, MediaKind::Manga));
+    field_list.push(("Is Adult?", or_na(media.is_adult), true));
+
+    media_embed(media, "Manga Result", field_list)
+}
+
+/// Builds the fields shared by every Media embed: names, description, dates and status.
+fn common_fields(media: &Media, want_long: bool) -> Vec<(&'static str, String, bool)> {
+    let mut field_list = vec![
+        ("English Name", media.display_title().to_string(), true),
+        ("Romaji Name", or_na(media.title.romaji.as_ref()), true),
+        (
+            "Description",
+            describe(media.description.as_deref(), want_long),
+            false,
+        ),
+    ];
+    field_list.extend(date_fields(media));
+
+    field_list
+}
+
+/// Start and end dates plus the release status.
+fn date_fields(media: &Media) -> Vec<(&'static str, String, bool)> {
+    let season = media.season.as_deref().unwrap_or("N/A");
+    let start_date = media
+        .start_date
+        .as_ref()
+        .map_or_else(|| String::from("N/A"), ToString::to_string);
+    let end_date = media
+        .end_date
+        .as_ref()
+        .map_or_else(|| String::from("N/A"), ToString::to_string);
+
+    vec![
+        ("Start Date", format!("{season} {start_date}"), true),
+        ("End Date", end_date, true),
+        ("Status", or_na(media.status.as_ref()), true),
+    ]
+}
+
+/// Episode or chapter counts followed by the scores.
+fn stat_fields(media: &Media, kind: MediaKind) -> Vec<(&'static str, String, bool)> {
+    let mut field_list = match kind {
+        MediaKind::Anime => vec![
+            ("Episode Count", or_na(media.episodes), true),
+            (
+                "Episode Length",
+                media
+                    .duration
+                    .map_or_else(|| String::from("N/A"), |d| format!("{d} minutes")),
+                true,
+            ),
+        ],
+        MediaKind::Manga => vec![
+            ("Volume Count", or_na(media.volumes), true),
+            ("Chapter Count", or_na(media.chapters), true),
+        ],
+    };
+    field_list.extend([
+        ("Average Score", or_na(media.average_score), true),
+        ("Mean Score", or_na(media.mean_score), true),
+    ]);
+
+    field_list
+}
+
+/// Builds the standard AniList embed for `media` with the given fields.
+fn media_embed(
+    media: &Media,
+    description: &str,
+    field_list: Vec<(&'static str, String, bool)>,
+) -> CreateEmbed {
+    let cover = media.cover_image.as_ref();
+
+    anilist_embed(
+        description,
+        media.colour(),
+        media.site_url.as_deref(),
+        cover.and_then(|c| c.large.as_deref()),
+        cover.and_then(|c| c.extra_large.as_deref()),
+        field_list,
+    )
+}
+
+/// Builds an embed in the AniList layout shared by every lookup command.
+///
+/// `icon` is shown next to the AniList link at the top and `image` is the large picture at the bottom.
+fn anilist_embed(
+    description: &str,
+    colour: u32,
+    site_url: Option<&str>,
+    icon: Option<&str>,
+    image: Option<&str>,
+    field_list: Vec<(&'static str, String, bool)>,
+) -> CreateEmbed {
+    let anilist_url = site_url.unwrap_or("https://anilist.co/");
+
+    let mut embed = CreateEmbed::default();
+    embed
+        .colour(colour)
+        .description(description)
+        .author(|f| {
+            if let Some(icon) = icon {
+                f.icon_url(icon);
+            }
+            f.name("AniList").url(anilist_url)
+        })
+        .fields(field_list);
+
+    if let Some(image) = image {
+        embed.image(image);
+    }
+
+    embed
+}
+
+/// Converts an AniList description to plain text and cuts it down to fit in an embed field.
+///
+/// AniList marks spoilers with `~!` and `!~`, those become Discord spoiler tags.
+fn describe(description: Option<&str>, want_long: bool) -> String {
+    let max_length = if want_long { 1024 } else { 512 };
+
+    // Leave room to close a spoiler the cut lands in, otherwise the rest of the embed would be hidden
+    let mut description = return_truncated(
+        from_read(description.unwrap_or("No description").as_bytes(), 50),
+        max_length - 2,
+    );
+    if description.matches("~!").count() > description.matches("!~").count() {
+        description.push_str("!~");
+    }
+
+    description.replace("~!", "||").replace("!~", "||")
+}
+
+/// Whether `media` has to be hidden because it's adult and adult content isn't allowed here.
+pub(crate) fn adult_hidden(media: &Media, allow_adult: bool) -> bool {
+    !allow_adult && media.is_adult == Some(true)
+}
+
+/// Whether the first entry of `connection` is hidden, an image of a character or person from it could be explicit too.
+fn top_is_hidden(connection: Option<&MediaConnection>, allow_adult: bool) -> bool {
+    connection
+        .and_then(|c| c.nodes.first())
+        .is_some_and(|media| adult_hidden(media, allow_adult))
+}
+
+/// Lists the top entries of a media connection as links, one per line, with adult titles hidden unless `allow_adult`.
+fn appearances(connection: Option<&MediaConnection>, allow_adult: bool) -> String {
+    let lines: Vec<String> = connection
+        .map(|c| c.nodes.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .map(|media| {
+            let kind = media.media_type.as_deref().unwrap_or("MEDIA");
+            if adult_hidden(media, allow_adult) {
+                return format!("Adult entry hidden ({kind})");
+            }
+
+            match media.site_url.as_deref() {
+                Some(url) => format!("[{}]({url}) ({kind})", media.display_title()),
+                None => format!("{} ({kind})", media.display_title()),
+            }
+        })
+        .collect();
+
+    if lines.is_empty() {
+        String::from("N/A")
+    } else {
+        return_truncated(lines.join("\n"), 1024)
+    }
+}
+
+/// Uploads `media` as AniList returned it for `query` in `format`, named after its kind and AniList ID like `anime_1.json`.
+async fn send_raw(
+    ctx: Context<'_>,
+    query: &str,
+    media: &Media,
+    kind: &str,
+    format: ExportFormat,
+) -> Result<(), Error> {
+    // The typed `Media` has every field any query asks for, so export the response itself rather than a pile of nulls
+    let raw = if let Some(raw) = ctx.data().anilist.raw_media_by_id(query, media.id).await? {
+        raw
+    } else {
+        ctx.say(format!("{kind} {} not found", media.id)).await?;
+        return Ok(());
+    };
+    let data = export_one(&raw, format)?;
+
+    ctx.send(|f| {
+        f.content(format!("{kind} result"))
+            .ephemeral(false)
+            .attachment(AttachmentType::Bytes {
+                data: std::borrow::Cow::Owned(data.into_bytes()),
+                filename: format!(
+                    "{}_{}.{}",
+                    kind.to_lowercase(),
+                    media.id,
+                    format.extension()
+                ),
+            })
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// Sends a single embed built ahead of time.
+async fn send_embed(ctx: Context<'_>, embed: CreateEmbed) -> Result<(), Error> {
+    ctx.send(|f| {
+        f.embed(|b| {
+            *b = embed;
+            b
+        })
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// Shows the first of `results` with Previous/Next/Select buttons so the user can pick the entry they meant.
+///
+/// A single result is sent straight away without any buttons. `links` gives the link buttons shown under each entry.
+async fn paginate_media(
+    ctx: Context<'_>,
+    results: &[Media],
+    build_embed: impl Fn(&Media) -> CreateEmbed + Send + Sync,
+    links: impl Fn(&Media) -> Vec<(String, String)> + Send + Sync,
+) -> Result<(), Error> {
+    let chosen = |index: usize| {
+        let mut components = CreateComponents::default();
+        add_link_buttons(&mut components, &links(&results[index]));
+        (build_embed(&results[index]), components)
+    };
+
+    let render = |index: usize| {
+        let (mut embed, components) = chosen(index);
+        if results.len() > 1 {
+            embed.footer(|f| f.text(format!("Result {} of {}", index + 1, results.len())));
+        }
+        (embed, components)
+    };
+
+    paginate(ctx, results.len(), render, Some(&chosen)).await
+}
+
+/// Trailer and official streaming site links for `media`, as (label, URL) pairs for [`add_link_buttons`].
+///
+/// Adult entries that can't be shown here get no links.
+fn media_links(media: &Media, allow_adult: bool) -> Vec<(String, String)> {
+    if adult_hidden(media, allow_adult) {
+        return Vec::new();
+    }
+
+    let trailer = media
+        .trailer
+        .as_ref()
+        .and_then(|t| t.url())
+        .map(|url| (String::from("Trailer"), url));
+
+    let streaming = media
+        .external_links
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .filter(|link| link.link_type.as_deref() == Some("STREAMING"))
+        .filter_map(|link| {
+            link.url
+                .as_ref()
+                .map(|url| (return_truncated(link.site.clone(), 80), url.clone()))
+        });
+
+    trailer.into_iter().chain(streaming).collect()
+}
+
+/// Adds a row of link buttons, Discord fits at most five in a row so the rest are left off.
+fn add_link_buttons(components: &mut CreateComponents, links: &[(String, String)]) {
+    if links.is_empty() {
+        return;
+    }
+
+    components.create_action_row(|r| {
+        for (label, url) in links.iter().take(5) {
+            r.create_button(|b| b.style(ButtonStyle::Link).label(label).url(url));
+        }
+        r
+    });
+}
+
+/// Displays an optional AniList value, using "N/A" when it's missing.
+fn or_na<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| String::from("N/A"), |v| v.to_string())
+}
@@ -1,3 +1,6 @@
 pub mod admin;
+pub mod airing;
 pub mod apis;
+pub mod paginate;
 pub mod quotes;
+pub mod watchlist;
@@ -0,0 +1,174 @@
+use std::time::Duration;
+
+use poise::serenity_prelude::{
+    ButtonStyle, CollectComponentInteraction, CreateComponents, CreateEmbed,
+    InteractionResponseType,
+};
+
+use crate::{Context, Error};
+
+/// How long pagination buttons stay active for
+pub const PAGINATION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How many lines each page of a listing shows
+pub const LINES_PER_PAGE: usize = 10;
+
+/// One page of a paginated reply, the embed and any components of its own (like link buttons).
+pub type Page = (CreateEmbed, CreateComponents);
+
+/// Shows `lines` in an embed [`LINES_PER_PAGE`] at a time with Previous/Next buttons.
+///
+/// A listing that fits on one page is sent without any buttons.
+pub async fn paginate_lines(
+    ctx: Context<'_>,
+    title: &str,
+    colour: u32,
+    lines: &[String],
+) -> Result<(), Error> {
+    let pages: Vec<&[String]> = lines.chunks(LINES_PER_PAGE).collect();
+    let page_count = pages.len().max(1);
+
+    let render = |index: usize| {
+        let mut embed = CreateEmbed::default();
+        embed
+            .title(title)
+            .colour(colour)
+            .description(pages.get(index).map_or_else(String::new, |p| p.join("\n")))
+            .footer(|f| f.text(format!("Page {} of {page_count}", index + 1)));
+        (embed, CreateComponents::default())
+    };
+
+    paginate(ctx, page_count, render, None).await
+}
+
+/// Shows page 0 of `page_count` from `render` with Previous/Next buttons under it, wrapping around at either end.
+///
+/// With `select` there's also a Select button, pressing it replaces the message with `select` of the current page
+/// and stops paging. A single page is sent without any buttons, and when the buttons time out the current page is
+/// left up without them.
+pub async fn paginate(
+    ctx: Context<'_>,
+    page_count: usize,
+    render: impl Fn(usize) -> Page + Send + Sync,
+    select: Option<&(dyn Fn(usize) -> Page + Send + Sync)>,
+) -> Result<(), Error> {
+    if page_count <= 1 {
+        let (embed, components) = render(0);
+        send_page(ctx, embed, components).await?;
+
+        return Ok(());
+    }
+
+    // Prefix the button IDs with the context ID so multiple listings don't pick up each others presses
+    let ctx_id = ctx.id();
+    let prev_button_id = format!("{ctx_id}prev");
+    let next_button_id = format!("{ctx_id}next");
+    let select_button_id = format!("{ctx_id}select");
+
+    let with_buttons = |index: usize| {
+        let (embed, mut components) = render(index);
+        components.create_action_row(|r| {
+            r.create_button(|b| {
+                b.custom_id(&prev_button_id)
+                    .label("Previous")
+                    .style(ButtonStyle::Secondary)
+            })
+            .create_button(|b| {
+                b.custom_id(&next_button_id)
+                    .label("Next")
+                    .style(ButtonStyle::Secondary)
+            });
+            if select.is_some() {
+                r.create_button(|b| {
+                    b.custom_id(&select_button_id)
+                        .label("Select")
+                        .style(ButtonStyle::Primary)
+                });
+            }
+            r
+        });
+        (embed, components)
+    };
+
+    let (first_page, first_components) = with_buttons(0);
+    let reply = send_page(ctx, first_page, first_components).await?;
+
+    let mut index = 0;
+    while let Some(press) = CollectComponentInteraction::new(ctx)
+        .author_id(ctx.author().id)
+        .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
+        .timeout(PAGINATION_TIMEOUT)
+        .await
+    {
+        let (embed, components) = match (select, press.data.custom_id.as_str()) {
+            (Some(select), id) if id == select_button_id => {
+                // Swap the page for the chosen one and drop the navigation buttons
+                let (embed, components) = select(index);
+                press
+                    .create_interaction_response(ctx, |r| {
+                        r.kind(InteractionResponseType::UpdateMessage)
+                            .interaction_response_data(|d| {
+                                d.set_embed(embed).set_components(components)
+                            })
+                    })
+                    .await?;
+
+                return Ok(());
+            }
+            (_, id) if id == next_button_id => {
+                index = (index + 1) % page_count;
+                with_buttons(index)
+            }
+            (_, id) if id == prev_button_id => {
+                index = index.checked_sub(1).unwrap_or(page_count - 1);
+                with_buttons(index)
+            }
+            _ => continue,
+        };
+
+        press
+            .create_interaction_response(ctx, |r| {
+                r.kind(InteractionResponseType::UpdateMessage)
+                    .interaction_response_data(|d| d.set_embed(embed).set_components(components))
+            })
+            .await?;
+    }
+
+    // Leave the current page up without the dead buttons
+    let (last_page, last_components) = render(index);
+    reply
+        .edit(ctx, |f| {
+            f.embed(|b| {
+                *b = last_page;
+                b
+            })
+            .components(|c| {
+                *c = last_components;
+                c
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+async fn send_page(
+    ctx: Context<'_>,
+    embed: CreateEmbed,
+    components: CreateComponents,
+) -> Result<poise::ReplyHandle<'_>, Error> {
+    let reply = ctx
+        .send(|f| {
+            f.embed(|b| {
+                *b = embed;
+                b
+            })
+            .components(|c| {
+                *c = components;
+                c
+            })
+        })
+        .await?;
+
+    Ok(reply)
+}
@@ -1,7 +1,15 @@
-use poise::serenity_prelude::UserId;
-use rusted_wumpus_lib::{checks::is_admin, structs::QuoteRow};
+use chrono::{DateTime, NaiveDate, Utc};
+use poise::serenity_prelude::{self as serenity, AttachmentType, UserId};
+use rusted_wumpus_lib::{
+    checks::{ensure_user_row, is_admin},
+    export::{export_rows, import_rows, ExportFormat},
+    structs::{QuoteImport, QuoteRow, QuoteSearchRow, QuoteTagRow},
+    utils::return_truncated,
+};
+use sqlx::PgPool;
 use tracing::instrument;
 
+use super::paginate::paginate_lines;
 use crate::{Context, Error};
 
 /// Gets a quote by ID
@@ -10,21 +18,25 @@ use crate::{Context, Error};
 pub async fn getquote(
     ctx: Context<'_>,
     #[description = "Quote ID"] quote_id: String,
+    #[description = "Only show the quote if it has this tag"] tag: Option<String>,
 ) -> Result<(), Error> {
-    let row: Option<QuoteRow> = sqlx::query_as("SELECT * FROM quotes WHERE (id) = ($1) LIMIT 1;")
-        .bind(quote_id.trim())
-        .fetch_optional(&ctx.data().db.clone())
-        .await?;
+    let tag = tag.as_deref().map(normalise_tag);
 
-    if let Some(q) = row {
-        let author_id = UserId::from(q.author.parse::<u64>()?);
-        let author = author_id.to_user(ctx).await?;
+    let row: Option<QuoteRow> = sqlx::query_as(
+        "SELECT * FROM quotes WHERE (id) = ($1) AND quote_visible(guild_id, $3)
+        AND ($2::text IS NULL OR EXISTS (SELECT 1 FROM quote_tags WHERE quote_id = quotes.id AND tag = $2)) LIMIT 1;",
+    )
+    .bind(quote_id.trim())
+    .bind(&tag)
+    .bind(guild_scope(ctx))
+    .fetch_optional(&ctx.data().db.clone())
+    .await?;
 
-        ctx.say(format!(
-            "Quote {}: {}\n Added by: {}",
-            q.id, q.quote, author.name
-        ))
-        .await?;
+    if let Some(q) = row {
+        ctx.say(quote_message(ctx, &q).await?).await?;
+    } else if let Some(tag) = tag {
+        ctx.say(format!("Quote {quote_id} not found with tag `{tag}`"))
+            .await?;
     } else {
         ctx.say(format!("Quote {quote_id} not found")).await?;
     }
@@ -35,27 +47,34 @@ pub async fn getquote(
 /// Gets a random quote
 #[instrument]
 #[poise::command(prefix_command, slash_command, category = "Quotes")]
-pub async fn randquote(ctx: Context<'_>) -> Result<(), Error> {
+pub async fn randquote(
+    ctx: Context<'_>,
+    #[description = "Only pick quotes with this tag"] tag: Option<String>,
+) -> Result<(), Error> {
     let pool = ctx.data().db.clone();
+    let tag = tag.as_deref().map(normalise_tag);
 
-    let quote: Option<QuoteRow> = sqlx::query_as("SELECT * FROM quotes ORDER BY random() LIMIT 1;")
-        .fetch_optional(&pool)
-        .await?;
+    let quote: Option<QuoteRow> = sqlx::query_as(
+        "SELECT * FROM quotes WHERE quote_visible(guild_id, $2)
+        AND ($1::text IS NULL OR EXISTS (SELECT 1 FROM quote_tags WHERE quote_id = quotes.id AND tag = $1))
+        ORDER BY random() LIMIT 1;",
+    )
+    .bind(&tag)
+    .bind(guild_scope(ctx))
+    .fetch_optional(&pool)
+    .await?;
 
     let quote = if let Some(quote_row) = quote {
         quote_row
+    } else if let Some(tag) = tag {
+        ctx.say(format!("No quotes found with tag `{tag}`")).await?;
+        return Ok(());
     } else {
         ctx.say("No quotes found").await?;
         return Ok(());
     };
 
-    let author_id = UserId::from(quote.author.parse::<u64>().unwrap());
-    let author = author_id.to_user(ctx).await?;
-    ctx.say(format!(
-        "Quote {}: {}\n Added by: {}",
-        quote.id, quote.quote, author.name
-    ))
-    .await?;
+    ctx.say(quote_message(ctx, &quote).await?).await?;
 
     Ok(())
 }
@@ -66,17 +85,39 @@ pub async fn randquote(ctx: Context<'_>) -> Result<(), Error> {
 pub async fn addquote(
     ctx: Context<'_>,
     #[description = "Quote contents"] quote: String,
+    #[description = "Who said it"] said_by: Option<serenity::User>,
+    #[description = "Who said it, if they aren't on Discord"] said_by_name: Option<String>,
 ) -> Result<(), Error> {
     // Prepare the database connection for the query.
     let pool = ctx.data().db.clone();
 
+    // Prefix commands have a message to point back to, slash commands don't.
+    let message_id = match ctx {
+        poise::Context::Prefix(prefix) => Some(prefix.msg.id.0.to_string()),
+        poise::Context::Application(_) => None,
+    };
+
+    // A Discord user wins over a free text name when both are given.
+    let said_by_name = said_by_name
+        .filter(|_| said_by.is_none())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty());
+
     // Create a new quote in the database and return the created row.
-    let row: QuoteRow =
-        sqlx::query_as("INSERT INTO quotes (quote, author) VALUES ($1, $2) RETURNING *")
-            .bind(quote.trim())
-            .bind(ctx.author().id.0.to_string())
-            .fetch_one(&pool)
-            .await?;
+    // Quotes added in DMs are kept to that DM, only the quotes from before guild scoping are global.
+    let row: QuoteRow = sqlx::query_as(
+        "INSERT INTO quotes (quote, added_by, said_by_id, said_by_name, channel_id, message_id, guild_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING *",
+    )
+    .bind(quote.trim())
+    .bind(ctx.author().id.0.to_string())
+    .bind(said_by.map(|user| user.id.0.to_string()))
+    .bind(said_by_name)
+    .bind(ctx.channel_id().0.to_string())
+    .bind(message_id)
+    .bind(guild_scope(ctx))
+    .fetch_one(&pool)
+    .await?;
 
     // Send a message saying the quote was added.
     ctx.say(format!("Added quote {}: {}", row.id, row.quote))
@@ -85,6 +126,50 @@ pub async fn addquote(
     Ok(())
 }
 
+/// Quote a message without retyping it
+#[instrument]
+#[poise::command(context_menu_command = "Quote message", category = "Quotes")]
+pub async fn quotemessage(
+    ctx: Context<'_>,
+    #[description = "Message to quote"] msg: serenity::Message,
+) -> Result<(), Error> {
+    let content = msg.content.trim();
+    if content.is_empty() {
+        ctx.say("That message has no text to quote").await?;
+        return Ok(());
+    }
+    if content.chars().count() > MAX_QUOTE_LENGTH {
+        ctx.say(format!(
+            "Quotes can be at most {MAX_QUOTE_LENGTH} characters long"
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    let said_at = DateTime::<Utc>::from_timestamp(msg.timestamp.unix_timestamp(), 0);
+
+    let row: QuoteRow = sqlx::query_as(
+        "INSERT INTO quotes (quote, added_by, said_by_id, said_at, channel_id, message_id, jump_url, guild_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING *",
+    )
+    .bind(content)
+    .bind(ctx.author().id.0.to_string())
+    .bind(msg.author.id.0.to_string())
+    .bind(said_at)
+    .bind(msg.channel_id.0.to_string())
+    .bind(msg.id.0.to_string())
+    // The resolved message from a context menu has no guild ID, so `msg.link()` would point at a DM
+    .bind(msg.id.link(msg.channel_id, ctx.guild_id()))
+    .bind(guild_scope(ctx))
+    .fetch_one(&ctx.data().db.clone())
+    .await?;
+
+    ctx.say(format!("Added quote {}: {}", row.id, row.quote))
+        .await?;
+
+    Ok(())
+}
+
 /// Delete a quote via ID
 #[instrument]
 #[poise::command(prefix_command, slash_command, category = "Quotes", check = "is_admin")]
@@ -95,13 +180,21 @@ pub async fn delquote(
     // Prepare the database connection for the query.
     let pool = ctx.data().db.clone();
 
-    // Delete the quote from the database.
-    let removed_row: QuoteRow = sqlx::query_as("DELETE FROM quotes WHERE (id) = ($1) RETURNING *;")
-        .bind(quote_id.trim())
-        .fetch_optional(&pool)
-        .await
-        .unwrap()
-        .expect("Failed to remove quote, probably because the quote with that ID doesn't exist");
+    // Delete the quote from the database, quotes shared from other guilds can only be removed there.
+    let removed_row: Option<QuoteRow> = sqlx::query_as(
+        "DELETE FROM quotes WHERE (id) = ($1) AND (guild_id IS NULL OR guild_id = $2) RETURNING *;",
+    )
+    .bind(quote_id.trim())
+    .bind(guild_scope(ctx))
+    .fetch_optional(&pool)
+    .await?;
+
+    let removed_row = if let Some(removed_row) = removed_row {
+        removed_row
+    } else {
+        ctx.say(format!("Quote {quote_id} not found")).await?;
+        return Ok(());
+    };
 
     // Send a message saying the quote was removed.
     ctx.say(format!(
@@ -112,3 +205,646 @@ pub async fn delquote(
 
     Ok(())
 }
+
+/// Export every quote visible here as a file
+#[instrument]
+#[poise::command(prefix_command, slash_command, category = "Quotes", check = "is_admin")]
+pub async fn exportquotes(
+    ctx: Context<'_>,
+    #[description = "File format, JSON by default"] format: Option<ExportFormat>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let format = format.unwrap_or(ExportFormat::Json);
+
+    let rows: Vec<QuoteRow> = sqlx::query_as(
+        "SELECT * FROM quotes WHERE quote_visible(guild_id, $1) ORDER BY created_at NULLS FIRST, id;",
+    )
+    .bind(guild_scope(ctx))
+    .fetch_all(&ctx.data().db.clone())
+    .await?;
+
+    if rows.is_empty() {
+        ctx.say("No quotes found").await?;
+        return Ok(());
+    }
+
+    let data = export_rows(&rows, format)?;
+
+    ctx.send(|f| {
+        f.content(format!("Exported {} quotes", rows.len()))
+            .attachment(AttachmentType::Bytes {
+                data: std::borrow::Cow::Owned(data.into_bytes()),
+                filename: format!("quotes.{}", format.extension()),
+            })
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// Import quotes from an `exportquotes` file or a spreadsheet
+#[instrument]
+#[poise::command(prefix_command, slash_command, category = "Quotes", check = "is_admin")]
+pub async fn importquotes(
+    ctx: Context<'_>,
+    #[description = "JSON, YAML or CSV file of quotes"] file: serenity::Attachment,
+    #[description = "File format, guessed from its name by default"] format: Option<ExportFormat>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let format = if let Some(format) = format.or_else(|| format_from_filename(&file.filename)) {
+        format
+    } else {
+        ctx.say(format!(
+            "Couldn't tell what format `{}` is in, pick one with the `format` option",
+            file.filename
+        ))
+        .await?;
+        return Ok(());
+    };
+
+    let data = if let Ok(data) = String::from_utf8(file.download().await?) {
+        data
+    } else {
+        ctx.say(format!(
+            "Couldn't read `{}`, it isn't UTF-8 text",
+            file.filename
+        ))
+        .await?;
+        return Ok(());
+    };
+    let rows: Vec<Result<QuoteImport, String>> = match import_rows(&data, format) {
+        Ok(rows) => rows,
+        Err(why) => {
+            ctx.say(format!("Couldn't read `{}`: {why}", file.filename))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let pool = ctx.data().db.clone();
+    let mut imported = 0;
+    let mut skipped = 0;
+    let mut errors = Vec::new();
+
+    for (index, row) in rows.into_iter().enumerate() {
+        let result = match row {
+            Ok(quote) => import_quote(ctx, &pool, quote).await,
+            Err(why) => Err(why.into()),
+        };
+
+        match result {
+            Ok(true) => imported += 1,
+            Ok(false) => skipped += 1,
+            Err(why) => errors.push(format!("Row {}: {why}", index + 1)),
+        }
+    }
+
+    let mut message = format!(
+        "Imported {imported} quotes, skipped {skipped} that already exist, {} rows failed",
+        errors.len()
+    );
+    if !errors.is_empty() {
+        message.push_str(&format!("\n{}", errors.join("\n")));
+    }
+
+    ctx.say(return_truncated(message, 2000)).await?;
+
+    Ok(())
+}
+
+/// Page through quotes, optionally filtered by who said or added them, a tag or when they were added
+#[instrument]
+#[poise::command(prefix_command, slash_command, category = "Quotes")]
+pub async fn listquotes(
+    ctx: Context<'_>,
+    #[description = "Only quotes said by this user"] said_by: Option<serenity::User>,
+    #[description = "Only quotes added by this user"] added_by: Option<serenity::User>,
+    #[description = "Only quotes with this tag"] tag: Option<String>,
+    #[description = "Added on or after, like 2026-03-01"] from: Option<String>,
+    #[description = "Added on or before, like 2026-03-31"] to: Option<String>,
+    #[description = "Order, newest first by default"] sort: Option<QuoteSort>,
+) -> Result<(), Error> {
+    let parse_date = |date: Option<String>| {
+        date.map(|d| NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d"))
+            .transpose()
+    };
+    let (from, to) = if let (Ok(from), Ok(to)) = (parse_date(from), parse_date(to)) {
+        (from, to)
+    } else {
+        ctx.say("Dates need to look like 2026-03-01").await?;
+        return Ok(());
+    };
+    if let (Some(from), Some(to)) = (from, to) {
+        if from > to {
+            ctx.say("The `from` date can't be after the `to` date")
+                .await?;
+            return Ok(());
+        }
+    }
+
+    let tag = tag.as_deref().map(normalise_tag);
+    let sort = sort.unwrap_or(QuoteSort::Newest);
+
+    // `from` and `to` filter on created_at, when the quote was added, not said_at from the quoted message.
+    // One past the limit is fetched to tell whether the list was cut short
+    let mut rows: Vec<QuoteRow> = sqlx::query_as(&format!(
+        "SELECT * FROM quotes WHERE quote_visible(guild_id, $1)
+        AND ($2::text IS NULL OR said_by_id = $2)
+        AND ($3::text IS NULL OR added_by = $3)
+        AND ($4::text IS NULL OR EXISTS (SELECT 1 FROM quote_tags WHERE quote_id = quotes.id AND tag = $4))
+        AND ($5::date IS NULL OR created_at >= $5)
+        AND ($6::date IS NULL OR created_at < $6 + 1)
+        ORDER BY {} LIMIT $7;",
+        sort.order_by()
+    ))
+    .bind(guild_scope(ctx))
+    .bind(said_by.as_ref().map(|user| user.id.0.to_string()))
+    .bind(added_by.as_ref().map(|user| user.id.0.to_string()))
+    .bind(&tag)
+    .bind(from)
+    .bind(to)
+    .bind(LIST_LIMIT + 1)
+    .fetch_all(&ctx.data().db.clone())
+    .await?;
+
+    if rows.is_empty() {
+        ctx.say("No quotes found").await?;
+        return Ok(());
+    }
+
+    let title = if rows.len() as i64 > LIST_LIMIT {
+        rows.truncate(LIST_LIMIT as usize);
+        format!("Quotes ({sort}, showing the first {LIST_LIMIT})")
+    } else {
+        format!("Quotes ({sort})")
+    };
+
+    let lines: Vec<String> = rows.iter().map(quote_line).collect();
+
+    paginate_lines(ctx, &title, QUOTE_COLOUR, &lines).await
+}
+
+/// Orders `listquotes` can show quotes in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum QuoteSort {
+    Newest,
+    Oldest,
+    Random,
+}
+
+impl QuoteSort {
+    /// Quotes from before `created_at` was recorded count as the oldest.
+    const fn order_by(self) -> &'static str {
+        match self {
+            Self::Newest => "created_at DESC NULLS LAST, id",
+            Self::Oldest => "created_at ASC NULLS FIRST, id",
+            Self::Random => "random()",
+        }
+    }
+}
+
+/// List the quotes someone said
+#[instrument]
+#[poise::command(prefix_command, slash_command, category = "Quotes")]
+pub async fn quotesby(
+    ctx: Context<'_>,
+    #[description = "Who said them"] member: serenity::User,
+) -> Result<(), Error> {
+    let rows: Vec<QuoteRow> = sqlx::query_as(
+        "SELECT * FROM quotes WHERE (said_by_id) = ($1) AND quote_visible(guild_id, $2)
+        ORDER BY created_at DESC NULLS LAST;",
+    )
+    .bind(member.id.0.to_string())
+    .bind(guild_scope(ctx))
+    .fetch_all(&ctx.data().db.clone())
+    .await?;
+
+    if rows.is_empty() {
+        ctx.say(format!("No quotes found from {}", member.name))
+            .await?;
+        return Ok(());
+    }
+
+    let lines: Vec<String> = rows.iter().map(quote_line).collect();
+
+    paginate_lines(
+        ctx,
+        &format!("Quotes from {}", member.name),
+        QUOTE_COLOUR,
+        &lines,
+    )
+    .await
+}
+
+/// Tag a quote with a topic
+#[instrument]
+#[poise::command(prefix_command, slash_command, category = "Quotes")]
+pub async fn tagquote(
+    ctx: Context<'_>,
+    #[description = "Quote ID"] quote_id: String,
+    #[description = "Tag"] tag: String,
+) -> Result<(), Error> {
+    let tag = normalise_tag(&tag);
+    if tag.is_empty() || tag.chars().count() > MAX_TAG_LENGTH {
+        ctx.say(format!(
+            "Tags need to be between 1 and {MAX_TAG_LENGTH} characters"
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    let pool = ctx.data().db.clone();
+
+    let quote = find_own_quote(ctx, &pool, quote_id.trim()).await?;
+
+    if quote.is_none() {
+        ctx.say(format!("Quote {quote_id} not found")).await?;
+        return Ok(());
+    }
+
+    let row: Option<QuoteTagRow> = sqlx::query_as(
+        "INSERT INTO quote_tags (quote_id, tag) VALUES ($1, $2) ON CONFLICT DO NOTHING RETURNING *;",
+    )
+    .bind(quote_id.trim())
+    .bind(&tag)
+    .fetch_optional(&pool)
+    .await?;
+
+    if let Some(row) = row {
+        ctx.say(format!("Tagged quote {} with `{}`", row.quote_id, row.tag))
+            .await?;
+    } else {
+        ctx.say(format!("Quote {quote_id} is already tagged `{tag}`"))
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Remove a tag from a quote, only the person who added the quote or an admin can do this
+#[instrument]
+#[poise::command(prefix_command, slash_command, category = "Quotes")]
+pub async fn untagquote(
+    ctx: Context<'_>,
+    #[description = "Quote ID"] quote_id: String,
+    #[description = "Tag"] tag: String,
+) -> Result<(), Error> {
+    let tag = normalise_tag(&tag);
+    let pool = ctx.data().db.clone();
+
+    let quote = find_own_quote(ctx, &pool, quote_id.trim()).await?;
+
+    let quote = if let Some(quote) = quote {
+        quote
+    } else {
+        ctx.say(format!("Quote {quote_id} not found")).await?;
+        return Ok(());
+    };
+
+    if quote.added_by != ctx.author().id.0.to_string() && !is_admin(ctx).await? {
+        ctx.say("Only the person who added this quote or an admin can untag it")
+            .await?;
+        return Ok(());
+    }
+
+    let row: Option<QuoteTagRow> =
+        sqlx::query_as("DELETE FROM quote_tags WHERE (quote_id, tag) = ($1, $2) RETURNING *;")
+            .bind(&quote.id)
+            .bind(&tag)
+            .fetch_optional(&pool)
+            .await?;
+
+    if row.is_some() {
+        ctx.say(format!("Removed tag `{tag}` from quote {}", quote.id))
+            .await?;
+    } else {
+        ctx.say(format!("Quote {} isn't tagged `{tag}`", quote.id))
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// List the tags on a quote, or every tag in use
+#[instrument]
+#[poise::command(prefix_command, slash_command, category = "Quotes")]
+pub async fn listtags(
+    ctx: Context<'_>,
+    #[description = "Quote ID"] quote_id: Option<String>,
+) -> Result<(), Error> {
+    let pool = ctx.data().db.clone();
+
+    if let Some(quote_id) = quote_id {
+        if find_quote(ctx, &pool, quote_id.trim()).await?.is_none() {
+            ctx.say(format!("Quote {quote_id} not found")).await?;
+            return Ok(());
+        }
+
+        let tags = tags_for(&pool, quote_id.trim()).await?;
+
+        if tags.is_empty() {
+            ctx.say(format!("Quote {quote_id} has no tags")).await?;
+        } else {
+            ctx.say(format!("Quote {quote_id} tags: {}", tags.join(", ")))
+                .await?;
+        }
+
+        return Ok(());
+    }
+
+    let counts: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT tag, count(*) FROM quote_tags JOIN quotes ON quotes.id = quote_tags.quote_id
+        WHERE quote_visible(quotes.guild_id, $1) GROUP BY tag ORDER BY count(*) DESC, tag;",
+    )
+    .bind(guild_scope(ctx))
+    .fetch_all(&pool)
+    .await?;
+
+    if counts.is_empty() {
+        ctx.say("No quotes have been tagged yet").await?;
+        return Ok(());
+    }
+
+    let tags: Vec<String> = counts
+        .iter()
+        .map(|(tag, count)| format!("{tag} ({count})"))
+        .collect();
+
+    ctx.say(return_truncated(
+        format!("Quote tags: {}", tags.join(", ")),
+        2000,
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Search quotes by their contents
+#[instrument]
+#[poise::command(prefix_command, slash_command, category = "Quotes")]
+pub async fn searchquote(
+    ctx: Context<'_>,
+    #[description = "Text to search for"]
+    #[rest]
+    text: String,
+) -> Result<(), Error> {
+    let text = text.trim();
+    if text.is_empty() {
+        ctx.say("Give me something to search for").await?;
+        return Ok(());
+    }
+
+    let pool = ctx.data().db.clone();
+
+    let mut rows: Vec<QuoteSearchRow> = sqlx::query_as(
+        "SELECT id, ts_headline('english', quote, query, 'StartSel=**, StopSel=**, MaxWords=25, MinWords=10') AS snippet,
+        ts_rank(search, query) AS rank
+        FROM quotes, websearch_to_tsquery('english', $1) query
+        WHERE search @@ query AND quote_visible(guild_id, $3) ORDER BY rank DESC LIMIT $2;",
+    )
+    .bind(text)
+    .bind(SEARCH_LIMIT)
+    .bind(guild_scope(ctx))
+    .fetch_all(&pool)
+    .await?;
+
+    // Nothing matched word for word, so look for quotes that are close enough instead
+    let fuzzy = rows.is_empty();
+    if fuzzy {
+        rows = sqlx::query_as(
+            "SELECT id, quote AS snippet, word_similarity($1, quote) AS rank
+            FROM quotes WHERE $1 <% quote AND quote_visible(guild_id, $3) ORDER BY rank DESC LIMIT $2;",
+        )
+        .bind(text)
+        .bind(SEARCH_LIMIT)
+        .bind(guild_scope(ctx))
+        .fetch_all(&pool)
+        .await?;
+    }
+
+    if rows.is_empty() {
+        ctx.say(format!("No quotes found matching `{text}`"))
+            .await?;
+        return Ok(());
+    }
+
+    let lines: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            format!(
+                "`{}` {}",
+                row.id,
+                return_truncated(row.snippet.replace('\n', " "), 200)
+            )
+        })
+        .collect();
+
+    let title = if fuzzy {
+        format!("Closest quotes to \"{text}\"")
+    } else {
+        format!("Quotes matching \"{text}\"")
+    };
+
+    paginate_lines(ctx, &return_truncated(title, 256), QUOTE_COLOUR, &lines).await
+}
+
+/// Embed colour for quote listings
+const QUOTE_COLOUR: u32 = 0x00f1_c40f;
+
+/// Most results `searchquote` will page through
+const SEARCH_LIMIT: i64 = 100;
+
+/// Most quotes `listquotes` will page through
+const LIST_LIMIT: i64 = 500;
+
+/// Longest quote allowed, matches the `quotes.quote` column
+const MAX_QUOTE_LENGTH: usize = 512;
+
+/// Longest tag allowed, matches the `quote_tags.tag` column
+const MAX_TAG_LENGTH: usize = 32;
+
+/// Tags are stored lowercase with dashes for spaces, so "Inside Jokes" and "inside-jokes" are the same tag.
+fn normalise_tag(tag: &str) -> String {
+    tag.split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase()
+}
+
+/// One line of a quote listing, like "`abc123` Hello there — @Alice, 2026-03-01".
+fn quote_line(row: &QuoteRow) -> String {
+    let mut attribution = Vec::new();
+    if let Some(said_by_id) = &row.said_by_id {
+        attribution.push(format!("<@{said_by_id}>"));
+    } else if let Some(said_by_name) = &row.said_by_name {
+        attribution.push(said_by_name.clone());
+    }
+    if let Some(date) = row.said_at.or(row.created_at) {
+        attribution.push(date.format("%Y-%m-%d").to_string());
+    }
+
+    let mut line = format!(
+        "`{}` {}",
+        row.id,
+        return_truncated(row.quote.replace('\n', " "), 200)
+    );
+    if !attribution.is_empty() {
+        line.push_str(&format!(" — {}", attribution.join(", ")));
+    }
+
+    line
+}
+
+/// Guesses the format of an import file from its extension.
+fn format_from_filename(filename: &str) -> Option<ExportFormat> {
+    let extension = filename.rsplit_once('.')?.1.to_lowercase();
+
+    [ExportFormat::Json, ExportFormat::Yaml, ExportFormat::Csv]
+        .into_iter()
+        .find(|format| {
+            format.extension() == extension || (extension == "yml" && *format == ExportFormat::Yaml)
+        })
+}
+
+/// Inserts one imported quote, returning `false` when a quote with its ID already exists.
+///
+/// Quotes without a guild are added to the guild the import was run in.
+async fn import_quote(ctx: Context<'_>, pool: &PgPool, quote: QuoteImport) -> Result<bool, Error> {
+    let text = quote.quote.trim();
+    if text.is_empty() {
+        return Err("quote is empty".into());
+    }
+    if text.chars().count() > MAX_QUOTE_LENGTH {
+        return Err(format!("quote is longer than {MAX_QUOTE_LENGTH} characters").into());
+    }
+
+    let added_by = quote
+        .added_by
+        .unwrap_or_else(|| ctx.author().id.0.to_string());
+    if added_by.parse::<u64>().is_err() {
+        return Err(format!("added_by `{added_by}` isn't a Discord user ID").into());
+    }
+    if let Some(said_by_id) = quote.said_by_id.as_deref() {
+        if said_by_id.parse::<u64>().is_err() {
+            return Err(format!("said_by_id `{said_by_id}` isn't a Discord user ID").into());
+        }
+    }
+
+    ensure_user_row(pool, &added_by).await?;
+
+    let row: Option<QuoteRow> = sqlx::query_as(
+        "INSERT INTO quotes (id, quote, added_by, said_by_id, said_by_name, created_at, said_at, channel_id, message_id, jump_url, guild_id)
+        VALUES (COALESCE($1, generate_uid(8)), $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        ON CONFLICT (id) DO NOTHING RETURNING *;",
+    )
+    .bind(quote.id.as_deref().map(str::trim).filter(|id| !id.is_empty()))
+    .bind(text)
+    .bind(&added_by)
+    .bind(&quote.said_by_id)
+    .bind(&quote.said_by_name)
+    .bind(quote.created_at)
+    .bind(quote.said_at)
+    .bind(&quote.channel_id)
+    .bind(&quote.message_id)
+    .bind(&quote.jump_url)
+    // The file's guild_id is ignored so admins can't slip quotes into guilds they don't run, or every guild
+    .bind(guild_scope(ctx))
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.is_some())
+}
+
+/// The scope quotes are stored and looked up in, the guild ID or `dm:<user ID>` in DMs.
+///
+/// DMs get a scope of their own so quotes added there don't turn up in every guild, only the quotes from
+/// before guild scoping have no scope and are global.
+fn guild_scope(ctx: Context<'_>) -> String {
+    ctx.guild_id().map_or_else(
+        || format!("dm:{}", ctx.author().id.0),
+        |guild_id| guild_id.0.to_string(),
+    )
+}
+
+/// Finds a quote by ID if it's visible from where the command was run.
+async fn find_quote(
+    ctx: Context<'_>,
+    pool: &PgPool,
+    quote_id: &str,
+) -> Result<Option<QuoteRow>, Error> {
+    let quote = sqlx::query_as(
+        "SELECT * FROM quotes WHERE (id) = ($1) AND quote_visible(guild_id, $2) LIMIT 1;",
+    )
+    .bind(quote_id)
+    .bind(guild_scope(ctx))
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(quote)
+}
+
+/// Finds a quote by ID if it belongs to where the command was run, quotes shared from other guilds can be read but not changed.
+async fn find_own_quote(
+    ctx: Context<'_>,
+    pool: &PgPool,
+    quote_id: &str,
+) -> Result<Option<QuoteRow>, Error> {
+    let quote = sqlx::query_as(
+        "SELECT * FROM quotes WHERE (id) = ($1) AND (guild_id IS NULL OR guild_id = $2) LIMIT 1;",
+    )
+    .bind(quote_id)
+    .bind(guild_scope(ctx))
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(quote)
+}
+
+async fn tags_for(pool: &PgPool, quote_id: &str) -> Result<Vec<String>, Error> {
+    let tags: Vec<QuoteTagRow> =
+        sqlx::query_as("SELECT * FROM quote_tags WHERE (quote_id) = ($1) ORDER BY tag;")
+            .bind(quote_id)
+            .fetch_all(pool)
+            .await?;
+
+    Ok(tags.into_iter().map(|t| t.tag).collect())
+}
+
+/// The message `getquote` and `randquote` reply with, attributed like "— Alice, 2026-03-01, added by Bob".
+async fn quote_message(ctx: Context<'_>, quote: &QuoteRow) -> Result<String, Error> {
+    let added_by = UserId::from(quote.added_by.parse::<u64>()?)
+        .to_user(ctx)
+        .await?;
+    let tags = tags_for(&ctx.data().db, &quote.id).await?;
+
+    let mut attribution = Vec::new();
+    if let Some(said_by_id) = &quote.said_by_id {
+        let said_by = UserId::from(said_by_id.parse::<u64>()?)
+            .to_user(ctx)
+            .await?;
+        attribution.push(said_by.name);
+    } else if let Some(said_by_name) = &quote.said_by_name {
+        attribution.push(said_by_name.clone());
+    }
+    if let Some(date) = quote.said_at.or(quote.created_at) {
+        attribution.push(date.format("%Y-%m-%d").to_string());
+    }
+    attribution.push(format!("added by {}", added_by.name));
+
+    let mut message = format!(
+        "Quote {}: {}\n— {}",
+        quote.id,
+        quote.quote,
+        attribution.join(", ")
+    );
+    if !tags.is_empty() {
+        message.push_str(&format!("\n Tags: {}", tags.join(", ")));
+    }
+    if let Some(jump_url) = &quote.jump_url {
+        message.push_str(&format!("\n Source: {jump_url}"));
+    }
+
+    Ok(message)
+}
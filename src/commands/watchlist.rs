@@ -0,0 +1,341 @@
+use poise::serenity_prelude as serenity;
+use rusted_wumpus_lib::{
+    anilist::DEFAULT_COLOUR, checks::adult_content_allowed, structs::WatchlistRow,
+};
+use tracing::instrument;
+
+use super::{
+    apis::{lookup_media, MediaKind, ADULT_BLOCKED},
+    paginate::paginate_lines,
+};
+use crate::{
+    vars::{ANIME_QUERY, MANGA_QUERY},
+    Context, Error,
+};
+
+/// Keep track of what you're watching and reading, no AniList account needed
+#[instrument]
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Fun",
+    subcommands(
+        "watchlist_add",
+        "watchlist_remove",
+        "watchlist_list",
+        "watchlist_status"
+    )
+)]
+pub async fn watchlist(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say("Use `watchlist add`, `watchlist remove`, `watchlist list` or `watchlist status`")
+        .await?;
+
+    Ok(())
+}
+
+/// Add an anime or manga to your watchlist
+#[instrument]
+#[poise::command(prefix_command, slash_command, rename = "add", category = "Fun")]
+pub async fn watchlist_add(
+    ctx: Context<'_>,
+    #[description = "Anime or manga"] kind: MediaKind,
+    #[description = "Name"] msg: String,
+    #[description = "Status, planning by default"] status: Option<WatchStatus>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let query = match kind {
+        MediaKind::Anime => ANIME_QUERY,
+        MediaKind::Manga => MANGA_QUERY,
+    };
+
+    let media = if let Some(media) = lookup_media(ctx, query, &msg).await?.into_iter().next() {
+        media
+    } else {
+        ctx.say(format!("No {} found for `{msg}`", kind.label()))
+            .await?;
+        return Ok(());
+    };
+
+    if media.is_adult == Some(true) && !adult_content_allowed(ctx).await? {
+        ctx.say(ADULT_BLOCKED).await?;
+        return Ok(());
+    }
+
+    let total = match kind {
+        MediaKind::Anime => media.episodes,
+        MediaKind::Manga => media.chapters,
+    };
+
+    let row: Option<WatchlistRow> = sqlx::query_as(
+        "INSERT INTO watchlist (user_id, media_id, media_type, title, status, total) VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (user_id, media_id) DO NOTHING RETURNING *;",
+    )
+    .bind(ctx.author().id.0.to_string())
+    .bind(media.id)
+    .bind(kind.as_anilist())
+    .bind(media.display_title())
+    .bind(status.unwrap_or(WatchStatus::Planning).as_str())
+    .bind(total)
+    .fetch_optional(&ctx.data().db.clone())
+    .await?;
+
+    if let Some(row) = row {
+        ctx.say(format!(
+            "Added {} to your watchlist as {}",
+            row.title,
+            WatchStatus::from_setting(&row.status)
+        ))
+        .await?;
+    } else {
+        ctx.say(format!(
+            "{} is already on your watchlist",
+            media.display_title()
+        ))
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Remove an entry from your watchlist
+#[instrument]
+#[poise::command(prefix_command, slash_command, rename = "remove", category = "Fun")]
+pub async fn watchlist_remove(
+    ctx: Context<'_>,
+    #[description = "Entry on your watchlist"]
+    #[autocomplete = "autocomplete_watchlist"]
+    entry: String,
+) -> Result<(), Error> {
+    let row = if let Some(row) = find_entry(ctx, &entry).await? {
+        row
+    } else {
+        ctx.say(format!("`{entry}` isn't on your watchlist"))
+            .await?;
+        return Ok(());
+    };
+
+    sqlx::query("DELETE FROM watchlist WHERE (user_id, media_id) = ($1, $2);")
+        .bind(&row.user_id)
+        .bind(row.media_id)
+        .execute(&ctx.data().db.clone())
+        .await?;
+
+    ctx.say(format!("Removed {} from your watchlist", row.title))
+        .await?;
+
+    Ok(())
+}
+
+/// Show your or another user's watchlist
+#[instrument]
+#[poise::command(prefix_command, slash_command, rename = "list", category = "Fun")]
+pub async fn watchlist_list(
+    ctx: Context<'_>,
+    #[description = "Whose watchlist to show"] member: Option<serenity::User>,
+    #[description = "Only show entries with this status"] status: Option<WatchStatus>,
+) -> Result<(), Error> {
+    let user = member.as_ref().unwrap_or_else(|| ctx.author());
+
+    let rows: Vec<WatchlistRow> = sqlx::query_as(
+        "SELECT * FROM watchlist WHERE (user_id) = ($1) AND ($2::text IS NULL OR status = $2)
+        ORDER BY updated_at DESC;",
+    )
+    .bind(user.id.0.to_string())
+    .bind(status.map(WatchStatus::as_str))
+    .fetch_all(&ctx.data().db.clone())
+    .await?;
+
+    if rows.is_empty() {
+        ctx.say(format!("{}'s watchlist is empty", user.name))
+            .await?;
+        return Ok(());
+    }
+
+    let lines: Vec<String> = rows.iter().map(watchlist_line).collect();
+
+    paginate_lines(
+        ctx,
+        &format!("{}'s watchlist", user.name),
+        DEFAULT_COLOUR,
+        &lines,
+    )
+    .await
+}
+
+/// Update the status, progress or score of a watchlist entry
+#[instrument]
+#[poise::command(prefix_command, slash_command, rename = "status", category = "Fun")]
+pub async fn watchlist_status(
+    ctx: Context<'_>,
+    #[description = "Entry on your watchlist"]
+    #[autocomplete = "autocomplete_watchlist"]
+    entry: String,
+    #[description = "New status"] status: Option<WatchStatus>,
+    #[description = "Episodes watched or chapters read"]
+    #[min = 0]
+    progress: Option<i32>,
+    #[description = "Score out of 10"]
+    #[min = 0]
+    #[max = 10]
+    score: Option<i32>,
+    #[description = "Remove the score"] clear_score: Option<bool>,
+) -> Result<(), Error> {
+    let clear_score = clear_score.unwrap_or(false);
+
+    let row = if let Some(row) = find_entry(ctx, &entry).await? {
+        row
+    } else {
+        ctx.say(format!("`{entry}` isn't on your watchlist"))
+            .await?;
+        return Ok(());
+    };
+
+    if status.is_none() && progress.is_none() && score.is_none() && !clear_score {
+        ctx.say(watchlist_line(&row)).await?;
+        return Ok(());
+    }
+
+    if progress.is_some_and(|p| p < 0) {
+        ctx.say("Progress can't be negative").await?;
+        return Ok(());
+    }
+    if let (Some(progress), Some(total)) = (progress, row.total) {
+        if progress > total {
+            let unit = if row.media_type == "MANGA" {
+                "chapters"
+            } else {
+                "episodes"
+            };
+            ctx.say(format!("{} only has {total} {unit}", row.title))
+                .await?;
+            return Ok(());
+        }
+    }
+    if score.is_some_and(|s| !(0..=10).contains(&s)) {
+        ctx.say("Scores are out of 10").await?;
+        return Ok(());
+    }
+    if clear_score && score.is_some() {
+        ctx.say("Pick either a new score or clearing it, not both")
+            .await?;
+        return Ok(());
+    }
+
+    // Finishing something fills in the progress when it wasn't given
+    let progress = match (status, progress, row.total) {
+        (_, Some(progress), _) => progress,
+        (Some(WatchStatus::Completed), None, Some(total)) => total,
+        _ => row.progress,
+    };
+    let score = if clear_score {
+        None
+    } else {
+        score.or(row.score)
+    };
+
+    let row: WatchlistRow = sqlx::query_as(
+        "UPDATE watchlist SET status = ($1), progress = ($2), score = ($3), updated_at = now()
+        WHERE (user_id, media_id) = ($4, $5) RETURNING *;",
+    )
+    .bind(status.map_or_else(|| row.status.clone(), |s| s.as_str().to_string()))
+    .bind(progress)
+    .bind(score)
+    .bind(&row.user_id)
+    .bind(row.media_id)
+    .fetch_one(&ctx.data().db.clone())
+    .await?;
+
+    ctx.say(format!("Updated {}", watchlist_line(&row))).await?;
+
+    Ok(())
+}
+
+/// Where an entry is at, stored in `watchlist.status`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum WatchStatus {
+    Planning,
+    Watching,
+    Completed,
+    Dropped,
+}
+
+impl WatchStatus {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Planning => "planning",
+            Self::Watching => "watching",
+            Self::Completed => "completed",
+            Self::Dropped => "dropped",
+        }
+    }
+
+    pub fn from_setting(status: &str) -> Self {
+        match status {
+            "watching" => Self::Watching,
+            "completed" => Self::Completed,
+            "dropped" => Self::Dropped,
+            _ => Self::Planning,
+        }
+    }
+}
+
+/// Suggests entries from the author's own watchlist, the value is the AniList ID.
+async fn autocomplete_watchlist(
+    ctx: Context<'_>,
+    partial: &str,
+) -> impl Iterator<Item = poise::AutocompleteChoice<String>> {
+    let rows: Vec<WatchlistRow> = sqlx::query_as(
+        "SELECT * FROM watchlist WHERE (user_id) = ($1) AND title ILIKE ('%' || $2 || '%') ESCAPE '\\'
+        ORDER BY updated_at DESC LIMIT 25;",
+    )
+    .bind(ctx.author().id.0.to_string())
+    .bind(escape_like(partial.trim()))
+    .fetch_all(&ctx.data().db.clone())
+    .await
+    .unwrap_or_default();
+
+    rows.into_iter().map(|row| poise::AutocompleteChoice {
+        name: row.title,
+        value: row.media_id.to_string(),
+    })
+}
+
+/// Finds one of the author's entries by AniList ID (what autocomplete sends) or by title.
+async fn find_entry(ctx: Context<'_>, entry: &str) -> Result<Option<WatchlistRow>, Error> {
+    let row = sqlx::query_as(
+        "SELECT * FROM watchlist WHERE (user_id) = ($1) AND (media_id::text = $2 OR lower(title) = lower($2))
+        ORDER BY updated_at DESC LIMIT 1;",
+    )
+    .bind(ctx.author().id.0.to_string())
+    .bind(entry.trim())
+    .fetch_optional(&ctx.data().db.clone())
+    .await?;
+
+    Ok(row)
+}
+
+/// Escapes `LIKE` wildcards so what the user typed is matched literally.
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// One line of a watchlist, like "**Cowboy Bebop** (anime) · Watching · 5/26 · 9/10".
+fn watchlist_line(row: &WatchlistRow) -> String {
+    let total = row
+        .total
+        .map_or_else(|| String::from("?"), |t| t.to_string());
+    let score = row
+        .score
+        .map_or_else(|| String::from("unscored"), |s| format!("{s}/10"));
+
+    format!(
+        "**{}** ({}) · {} · {}/{total} · {score}",
+        row.title,
+        row.media_type.to_lowercase(),
+        WatchStatus::from_setting(&row.status),
+        row.progress
+    )
+}
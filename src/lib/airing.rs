@@ -0,0 +1,62 @@
+//! Bookkeeping for airing announcements, kept apart from the Discord side so it can be tested.
+
+use crate::anilist::AiringSchedule;
+
+/// How far a subscription's episodes have been announced, stored in `anime_subscriptions`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AiringCursor {
+    /// Unix timestamp the airing schedule has been checked up to
+    pub checked_until: i64,
+    /// Newest episode announced
+    pub last_episode: i32,
+}
+
+impl AiringCursor {
+    /// The episodes of `media_id` in `schedules` that still need announcing, oldest first.
+    ///
+    /// `last_episode` stops a window overlapping an earlier one announcing the same episode twice.
+    pub fn pending<'a>(
+        &self,
+        media_id: i64,
+        schedules: &'a [AiringSchedule],
+    ) -> Vec<&'a AiringSchedule> {
+        let mut pending: Vec<&AiringSchedule> = schedules
+            .iter()
+            .filter(|s| {
+                s.media_id == media_id
+                    && s.airing_at >= self.checked_until
+                    && s.episode > self.last_episode
+            })
+            .collect();
+        pending.sort_by_key(|s| (s.airing_at, s.episode));
+        pending
+    }
+
+    pub fn announced(&mut self, schedule: &AiringSchedule) {
+        self.last_episode = self.last_episode.max(schedule.episode);
+    }
+
+    /// Stops at an episode that couldn't be announced, so the next poll starts from it.
+    pub fn failed(&mut self, schedule: &AiringSchedule) {
+        self.checked_until = schedule.airing_at;
+    }
+
+    /// Everything that aired before `to` went out.
+    pub fn finished(&mut self, to: i64) {
+        self.checked_until = to;
+    }
+}
+
+/// The exclusive `from` and `to` bounds to ask AniList for, `None` when there's nothing subscribed.
+///
+/// The window starts at the oldest `checked_until`, but never further back than `max_catch_up` seconds.
+pub fn poll_window(
+    checked_until: impl IntoIterator<Item = i64>,
+    now: i64,
+    max_catch_up: i64,
+) -> Option<(i64, i64)> {
+    let from = checked_until.into_iter().min()?.max(now - max_catch_up);
+
+    // AniList's bounds are both exclusive, starting a second early makes the window include `from`
+    Some((from - 1, now))
+}
@@ -0,0 +1,772 @@
+use std::{fmt, sync::Arc};
+
+use reqwest::{Client, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::{event, Level};
+
+use crate::{
+    cache::{cache_key, ResponseCache},
+    ratelimit::{backoff, retry_after, should_retry, RateLimited, RateLimiter, MAX_ATTEMPTS},
+    types::Error,
+};
+
+/// The public AniList GraphQL endpoint, used unless `ANILIST_URL` is set.
+pub const ANILIST_URL: &str = "https://graphql.anilist.co/";
+
+/// Light blue used for embeds when AniList doesn't have a colour for an entry.
+pub const DEFAULT_COLOUR: u32 = 0x00ae_d6f1;
+
+/// Thin wrapper around a shared `reqwest::Client` for talking to AniList.
+///
+/// `reqwest::Client` is reference counted internally, so cloning this is cheap and every clone shares the same connection pool.
+#[derive(Debug, Clone)]
+pub struct AniList {
+    http: Client,
+    endpoint: String,
+    cache: Option<Arc<ResponseCache>>,
+    limiter: Arc<RateLimiter>,
+}
+
+impl AniList {
+    /// Creates a client that sends its queries to `endpoint`, normally [`ANILIST_URL`].
+    pub fn new(http: Client, endpoint: impl Into<String>) -> Self {
+        Self {
+            http,
+            endpoint: endpoint.into(),
+            cache: None,
+            limiter: Arc::new(RateLimiter::default()),
+        }
+    }
+
+    /// Answers repeat queries from `cache` instead of asking AniList again.
+    #[must_use]
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(Arc::new(cache));
+        self
+    }
+
+    pub fn cache(&self) -> Option<&ResponseCache> {
+        self.cache.as_deref()
+    }
+
+    /// Sends a GraphQL query to AniList and deserializes the `data` object into `T`.
+    ///
+    /// Responses are served from the cache when one is set, errors are never cached.
+    pub async fn query<T: DeserializeOwned>(
+        &self,
+        query: &str,
+        variables: Value,
+    ) -> Result<T, Error> {
+        let data = if let Some(cache) = &self.cache {
+            let key = cache_key(query, &variables);
+
+            if let Some(data) = cache.get(&key).await {
+                data
+            } else {
+                let data = self.fetch(query, variables).await?;
+                cache.insert(key, data.clone()).await;
+                data
+            }
+        } else {
+            self.fetch(query, variables).await?
+        };
+
+        Ok(serde_json::from_value(data)?)
+    }
+
+    /// Sends a GraphQL query to AniList, always skipping the cache.
+    ///
+    /// Network and server errors are retried with backoff, and short rate limits are waited out.
+    /// Longer rate limits come back as a [`RateLimited`] error so the user can be told when to try again.
+    /// Errors reported by AniList in the `errors` array are turned into an `Err` with their messages joined together.
+    pub async fn fetch(&self, query: &str, variables: Value) -> Result<Value, Error> {
+        let body = json!({ "query": query, "variables": variables }).to_string();
+
+        let mut attempt = 0;
+        let (status, text) = loop {
+            attempt += 1;
+            self.limiter.wait().await?;
+
+            let resp = match self
+                .http
+                .post(&self.endpoint)
+                .header("Content-Type", "application/json")
+                .header("Accept", "application/json")
+                .body(body.clone())
+                .send()
+                .await
+            {
+                Ok(resp) => resp,
+                Err(why) if attempt < MAX_ATTEMPTS => {
+                    event!(Level::WARN, "AniList request failed, retrying." = %why);
+                    tokio::time::sleep(backoff(attempt)).await;
+                    continue;
+                }
+                Err(why) => return Err(why.into()),
+            };
+
+            self.limiter.update(resp.headers());
+
+            let status = resp.status();
+            if status == StatusCode::TOO_MANY_REQUESTS {
+                let retry_after = retry_after(resp.headers());
+                self.limiter.block_for(retry_after);
+
+                // The next `wait` sleeps out the block before retrying
+                if should_retry(attempt, retry_after) {
+                    continue;
+                }
+
+                return Err(RateLimited { retry_after }.into());
+            }
+
+            if status.is_server_error() && attempt < MAX_ATTEMPTS {
+                tokio::time::sleep(backoff(attempt)).await;
+                continue;
+            }
+
+            break (status, resp.text().await?);
+        };
+
+        // AniList still sends a GraphQL body on 4xx responses, so only fall back to the status when the body isn't one
+        let resp: GraphQlResponse<Value> = match serde_json::from_str(&text) {
+            Ok(resp) => resp,
+            Err(_) if !status.is_success() => {
+                return Err(format!("AniList returned HTTP {status}").into())
+            }
+            Err(why) => return Err(why.into()),
+        };
+
+        match (resp.data, resp.errors) {
+            (_, Some(errors)) => Err(errors
+                .into_iter()
+                .map(|e| e.message)
+                .collect::<Vec<_>>()
+                .join(", ")
+                .into()),
+            (Some(data), None) if !data.is_null() => Ok(data),
+            _ => Err("AniList returned an empty response".into()),
+        }
+    }
+
+    /// Looks up the single best matching `Media` for `search` using a `Media(search:)` query.
+    ///
+    /// Returns `Ok(None)` when AniList has no match.
+    pub async fn search_media(&self, query: &str, search: &str) -> Result<Option<Media>, Error> {
+        let data: Option<MediaData> = self.find_one(query, json!({ "search": search })).await?;
+
+        Ok(data.and_then(|d| d.media))
+    }
+
+    /// Looks up a character with `CHARACTER_QUERY` from `vars.rs`.
+    pub async fn search_character(
+        &self,
+        query: &str,
+        search: &str,
+    ) -> Result<Option<Character>, Error> {
+        let data: Option<CharacterData> = self.find_one(query, json!({ "search": search })).await?;
+
+        Ok(data.and_then(|d| d.character))
+    }
+
+    /// Looks up a staff member (voice actor, director, author...) with `STAFF_QUERY` from `vars.rs`.
+    pub async fn search_staff(&self, query: &str, search: &str) -> Result<Option<Staff>, Error> {
+        let data: Option<StaffData> = self.find_one(query, json!({ "search": search })).await?;
+
+        Ok(data.and_then(|d| d.staff))
+    }
+
+    /// Looks up a studio with `STUDIO_QUERY` from `vars.rs`.
+    pub async fn search_studio(&self, query: &str, search: &str) -> Result<Option<Studio>, Error> {
+        let data: Option<StudioData> = self.find_one(query, json!({ "search": search })).await?;
+
+        Ok(data.and_then(|d| d.studio))
+    }
+
+    /// Looks up an AniList user's profile and statistics by name with `USER_QUERY` from `vars.rs`.
+    pub async fn user(&self, query: &str, name: &str) -> Result<Option<User>, Error> {
+        let data: Option<UserData> = self.find_one(query, json!({ "name": name })).await?;
+
+        Ok(data.and_then(|d| d.user))
+    }
+
+    /// Fetches a user's latest list updates with `USER_ACTIVITY_QUERY` from `vars.rs`.
+    pub async fn recent_activity(
+        &self,
+        query: &str,
+        user_id: i64,
+    ) -> Result<Vec<ListActivity>, Error> {
+        let data: ActivityPageData = self.query(query, json!({ "userId": user_id })).await?;
+
+        Ok(data.page.activities)
+    }
+
+    /// Fetches one page of the entries matching `filters`, `page_info.total` says how many there are altogether.
+    pub async fn filtered_media(
+        &self,
+        query: &str,
+        filters: &MediaFilters,
+        page: i32,
+        per_page: i32,
+    ) -> Result<Page, Error> {
+        let mut variables = filters.variables();
+        variables["page"] = json!(page);
+        variables["perPage"] = json!(per_page);
+
+        let data: PageData = self.query(query, variables).await?;
+
+        Ok(data.page)
+    }
+
+    /// Fetches every episode of `media_ids` that aired between the `from` and `to` unix timestamps.
+    ///
+    /// This always skips the cache, it's polled to find out what just aired.
+    pub async fn airing_schedules(
+        &self,
+        query: &str,
+        media_ids: &[i64],
+        from: i64,
+        to: i64,
+    ) -> Result<Vec<AiringSchedule>, Error> {
+        let mut schedules = Vec::new();
+
+        for page in 1.. {
+            let variables = json!({ "mediaIds": media_ids, "from": from, "to": to, "page": page });
+            let data: AiringPageData = serde_json::from_value(self.fetch(query, variables).await?)?;
+
+            schedules.extend(data.page.airing_schedules);
+
+            if !data.page.page_info.is_some_and(|p| p.has_next_page) {
+                break;
+            }
+        }
+
+        Ok(schedules)
+    }
+
+    /// Runs a single entry query, turning AniList's "Not Found" error into `Ok(None)`.
+    async fn find_one<T: DeserializeOwned>(
+        &self,
+        query: &str,
+        variables: Value,
+    ) -> Result<Option<T>, Error> {
+        match self.query(query, variables).await {
+            Ok(data) => Ok(Some(data)),
+            // AniList reports a missing entry as a 404 error rather than a null result
+            Err(why) if why.to_string().contains("Not Found") => Ok(None),
+            Err(why) => Err(why),
+        }
+    }
+
+    /// Searches with one of the `Page { media(search:) }` queries in `vars.rs`, returning up to `per_page` candidates.
+    ///
+    /// An empty `Vec` means nothing matched.
+    pub async fn search_media_page(
+        &self,
+        query: &str,
+        search: &str,
+        per_page: i32,
+    ) -> Result<Vec<Media>, Error> {
+        let data: PageData = self
+            .query(query, json!({ "search": search, "perPage": per_page }))
+            .await?;
+
+        Ok(data.page.media)
+    }
+
+    /// Fetches the exact `Media` with AniList ID `id` using the same `Page` queries as [`AniList::search_media_page`].
+    pub async fn media_by_id(&self, query: &str, id: i64) -> Result<Option<Media>, Error> {
+        let data: PageData = self.query(query, json!({ "id": id, "perPage": 1 })).await?;
+
+        Ok(data.page.media.into_iter().next())
+    }
+
+    /// Fetches the entry with AniList ID `id` exactly as AniList sent it, with only the fields `query` asked for.
+    ///
+    /// Shares its cache entry with [`AniList::media_by_id`].
+    pub async fn raw_media_by_id(&self, query: &str, id: i64) -> Result<Option<Value>, Error> {
+        let data: Value = self.query(query, json!({ "id": id, "perPage": 1 })).await?;
+
+        Ok(data["Page"]["media"].get(0).cloned())
+    }
+
+    /// Fetches a single entry by `id`, or the best match for `search`, with `media_type` (`ANIME` or `MANGA`) limiting the search.
+    ///
+    /// Used with `RELATED_QUERY`, which works for either type.
+    pub async fn media_of_type(
+        &self,
+        query: &str,
+        id: Option<i64>,
+        search: Option<&str>,
+        media_type: &str,
+    ) -> Result<Option<Media>, Error> {
+        let variables = if let Some(id) = id {
+            json!({ "id": id })
+        } else {
+            json!({ "search": search, "type": media_type })
+        };
+        let data: PageData = self.query(query, variables).await?;
+
+        Ok(data.page.media.into_iter().next())
+    }
+
+    /// Runs the lightweight `TITLE_SEARCH_QUERY` from `vars.rs`, only `id` and `title` are filled in on the results.
+    ///
+    /// `media_type` is AniList's `MediaType`, either `ANIME` or `MANGA`.
+    pub async fn search_titles(
+        &self,
+        query: &str,
+        search: &str,
+        media_type: &str,
+    ) -> Result<Vec<Media>, Error> {
+        let data: PageData = self
+            .query(query, json!({ "search": search, "type": media_type }))
+            .await?;
+
+        Ok(data.page.media)
+    }
+
+    /// Fetches the anime airing in `season` of `year`, following AniList's pages up to `max_pages`.
+    ///
+    /// `season` is AniList's `MediaSeason` and `sort` a `MediaSort` such as `POPULARITY_DESC`.
+    pub async fn seasonal_media(
+        &self,
+        query: &str,
+        season: &str,
+        year: i32,
+        sort: &str,
+        max_pages: i32,
+    ) -> Result<Vec<Media>, Error> {
+        let mut media = Vec::new();
+
+        for page in 1..=max_pages {
+            let variables =
+                json!({ "season": season, "seasonYear": year, "sort": [sort], "page": page });
+            let data: PageData = self.query(query, variables).await?;
+
+            media.extend(data.page.media);
+
+            if !data.page.page_info.is_some_and(|p| p.has_next_page) {
+                break;
+            }
+        }
+
+        Ok(media)
+    }
+}
+
+/// Filters for the random picker queries, anything left unset matches everything.
+#[derive(Debug, Clone, Default)]
+pub struct MediaFilters {
+    /// Entries need at least one of these genres
+    pub genres: Vec<String>,
+    /// Lowest `averageScore` to include
+    pub min_score: Option<i32>,
+    /// AniList `MediaFormat`, like `TV` or `MOVIE`
+    pub format: Option<String>,
+    pub year_from: Option<i32>,
+    pub year_to: Option<i32>,
+    pub exclude_adult: bool,
+}
+
+impl MediaFilters {
+    /// GraphQL variables for the filters, years become the `FuzzyDateInt` (`YYYYMMDD`) bounds AniList expects.
+    pub fn variables(&self) -> Value {
+        json!({
+            "genres": (!self.genres.is_empty()).then_some(&self.genres),
+            // AniList's score and date filters are exclusive
+            "minScore": self.min_score.map(|score| score - 1),
+            "format": self.format,
+            "startFrom": self.year_from.map(|year| year * 10000 - 1),
+            "startTo": self.year_to.map(|year| (year + 1) * 10000),
+            "isAdult": self.exclude_adult.then_some(false),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlResponse<T> {
+    data: Option<T>,
+    errors: Option<Vec<GraphQlError>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MediaData {
+    #[serde(rename = "Media")]
+    pub media: Option<Media>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PageData {
+    #[serde(rename = "Page")]
+    pub page: Page,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page {
+    pub page_info: Option<PageInfo>,
+    #[serde(default)]
+    pub media: Vec<Media>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Media {
+    pub id: i64,
+    pub title: MediaTitle,
+    /// `ANIME` or `MANGA`
+    #[serde(rename = "type")]
+    pub media_type: Option<String>,
+    /// `TV`, `MOVIE`, `MANGA`, `NOVEL` and so on
+    pub format: Option<String>,
+    pub status: Option<String>,
+    pub description: Option<String>,
+    pub start_date: Option<FuzzyDate>,
+    pub end_date: Option<FuzzyDate>,
+    pub cover_image: Option<CoverImage>,
+    pub season: Option<String>,
+    pub season_year: Option<i32>,
+    pub season_int: Option<i32>,
+    pub episodes: Option<i32>,
+    pub duration: Option<i32>,
+    pub volumes: Option<i32>,
+    pub chapters: Option<i32>,
+    pub hashtag: Option<String>,
+    pub trailer: Option<MediaTrailer>,
+    pub genres: Option<Vec<String>>,
+    pub average_score: Option<i32>,
+    pub mean_score: Option<i32>,
+    pub popularity: Option<i32>,
+    pub is_adult: Option<bool>,
+    pub site_url: Option<String>,
+    pub external_links: Option<Vec<MediaExternalLink>>,
+    pub streaming_episodes: Option<Vec<MediaStreamingEpisode>>,
+    pub relations: Option<MediaConnection>,
+    pub recommendations: Option<RecommendationConnection>,
+}
+
+impl Media {
+    /// English title if there is one, otherwise the romaji title.
+    pub fn display_title(&self) -> &str {
+        self.title
+            .english
+            .as_deref()
+            .or(self.title.romaji.as_deref())
+            .unwrap_or("Unknown")
+    }
+
+    /// Cover colour as an RGB integer, falling back to a light blue when AniList doesn't have one.
+    pub fn colour(&self) -> u32 {
+        self.cover_image
+            .as_ref()
+            .and_then(|c| c.color.as_deref())
+            .and_then(|c| u32::from_str_radix(c.trim_start_matches('#'), 16).ok())
+            .unwrap_or(DEFAULT_COLOUR)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MediaTitle {
+    pub romaji: Option<String>,
+    pub english: Option<String>,
+    pub native: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FuzzyDate {
+    pub year: Option<i32>,
+    pub month: Option<i32>,
+    pub day: Option<i32>,
+}
+
+impl fmt::Display for FuzzyDate {
+    /// Formats as `year/month/day`, with `?` standing in for unknown parts.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let part = |p: Option<i32>| p.map_or_else(|| String::from("?"), |p| p.to_string());
+
+        write!(
+            f,
+            "{}/{}/{}",
+            part(self.year),
+            part(self.month),
+            part(self.day)
+        )
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverImage {
+    pub extra_large: Option<String>,
+    pub large: Option<String>,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MediaTrailer {
+    pub id: Option<String>,
+    pub site: Option<String>,
+    pub thumbnail: Option<String>,
+}
+
+impl MediaTrailer {
+    /// Watch URL for the trailer, AniList only ever has YouTube and Dailymotion ones.
+    pub fn url(&self) -> Option<String> {
+        let id = self.id.as_deref()?;
+
+        match self.site.as_deref()? {
+            "youtube" => Some(format!("https://www.youtube.com/watch?v={id}")),
+            "dailymotion" => Some(format!("https://www.dailymotion.com/video/{id}")),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MediaExternalLink {
+    pub url: Option<String>,
+    pub site: String,
+    /// `INFO`, `STREAMING` or `SOCIAL`
+    #[serde(rename = "type")]
+    pub link_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MediaStreamingEpisode {
+    pub title: Option<String>,
+    pub thumbnail: Option<String>,
+    pub url: Option<String>,
+    pub site: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CharacterData {
+    #[serde(rename = "Character")]
+    pub character: Option<Character>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StaffData {
+    #[serde(rename = "Staff")]
+    pub staff: Option<Staff>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StudioData {
+    #[serde(rename = "Studio")]
+    pub studio: Option<Studio>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Character {
+    pub id: i64,
+    pub name: Name,
+    pub image: Option<Image>,
+    pub description: Option<String>,
+    pub favourites: Option<i32>,
+    pub site_url: Option<String>,
+    pub media: Option<MediaConnection>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Staff {
+    pub id: i64,
+    pub name: Name,
+    pub image: Option<Image>,
+    pub description: Option<String>,
+    pub primary_occupations: Option<Vec<String>>,
+    pub favourites: Option<i32>,
+    pub site_url: Option<String>,
+    pub staff_media: Option<MediaConnection>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Studio {
+    pub id: i64,
+    pub name: String,
+    pub is_animation_studio: Option<bool>,
+    pub favourites: Option<i32>,
+    pub site_url: Option<String>,
+    pub media: Option<MediaConnection>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Name {
+    pub full: Option<String>,
+    pub native: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Image {
+    pub large: Option<String>,
+    pub medium: Option<String>,
+}
+
+/// A list of related `Media`, usually only `id`, `title`, `type` and `siteUrl` are requested.
+///
+/// `edges` is only filled in for `relations`, where it carries how each entry is related.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MediaConnection {
+    #[serde(default)]
+    pub nodes: Vec<Media>,
+    #[serde(default)]
+    pub edges: Vec<MediaEdge>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaEdge {
+    /// `SEQUEL`, `PREQUEL`, `SIDE_STORY`, `ADAPTATION` and so on
+    pub relation_type: Option<String>,
+    pub node: Option<Media>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RecommendationConnection {
+    #[serde(default)]
+    pub nodes: Vec<Recommendation>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recommendation {
+    pub rating: Option<i32>,
+    pub media_recommendation: Option<Media>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserData {
+    #[serde(rename = "User")]
+    pub user: Option<User>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    pub id: i64,
+    pub name: String,
+    pub site_url: Option<String>,
+    pub avatar: Option<Image>,
+    pub options: Option<UserOptions>,
+    pub statistics: Option<UserStatisticTypes>,
+}
+
+impl User {
+    /// The user's chosen profile colour, AniList stores either a preset name or a hex code.
+    pub fn colour(&self) -> u32 {
+        let profile_colour = self
+            .options
+            .as_ref()
+            .and_then(|o| o.profile_color.as_deref())
+            .unwrap_or("blue");
+
+        match profile_colour {
+            "blue" => 0x003d_b4f2,
+            "purple" => 0x00c0_63ff,
+            "pink" => 0x00fc_9dd6,
+            "orange" => 0x00ef_881a,
+            "red" => 0x00e1_3333,
+            "green" => 0x004c_ca51,
+            "gray" => 0x0067_7b94,
+            hex => u32::from_str_radix(hex.trim_start_matches('#'), 16).unwrap_or(DEFAULT_COLOUR),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOptions {
+    pub profile_color: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserStatisticTypes {
+    pub anime: Option<UserStatistics>,
+    pub manga: Option<UserStatistics>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserStatistics {
+    pub count: Option<i32>,
+    pub mean_score: Option<f64>,
+    pub minutes_watched: Option<i32>,
+    pub episodes_watched: Option<i32>,
+    pub chapters_read: Option<i32>,
+    pub volumes_read: Option<i32>,
+    #[serde(default)]
+    pub genres: Vec<GenreStatistic>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GenreStatistic {
+    pub genre: Option<String>,
+    pub count: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActivityPageData {
+    #[serde(rename = "Page")]
+    pub page: ActivityPage,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActivityPage {
+    #[serde(default)]
+    pub activities: Vec<ListActivity>,
+}
+
+/// An entry from a user's activity feed, like "Watched episode 3 of ..."
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListActivity {
+    pub status: Option<String>,
+    pub progress: Option<String>,
+    /// Unix timestamp
+    pub created_at: Option<i64>,
+    pub media: Option<Media>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AiringPageData {
+    #[serde(rename = "Page")]
+    pub page: AiringPage,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiringPage {
+    pub page_info: Option<PageInfo>,
+    #[serde(default)]
+    pub airing_schedules: Vec<AiringSchedule>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageInfo {
+    #[serde(default)]
+    pub has_next_page: bool,
+    /// Total matches across every page, AniList caps this for very broad queries
+    pub total: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiringSchedule {
+    pub id: i64,
+    pub episode: i32,
+    /// Unix timestamp
+    pub airing_at: i64,
+    pub media_id: i64,
+    pub media: Option<Media>,
+}
@@ -0,0 +1,43 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use tracing_unwrap::ResultExt;
+
+/// How long to wait for the user to stop typing before searching
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Debounces the AniList title searches behind slash command autocomplete.
+///
+/// Discord sends an autocomplete request for every keystroke, so without this each letter typed would be its own AniList query.
+/// Repeat searches are answered by the AniList client's own cache.
+#[derive(Debug, Default)]
+pub struct TitleAutocomplete {
+    next_ticket: AtomicU64,
+    latest: Mutex<HashMap<u64, u64>>,
+}
+
+impl TitleAutocomplete {
+    /// Waits a moment and returns `true` if this is still the newest request from `user_id`.
+    ///
+    /// Requests that were superseded by another keystroke in the meantime get `false` and should be dropped.
+    pub async fn debounce(&self, user_id: u64) -> bool {
+        let ticket = self.next_ticket.fetch_add(1, Ordering::Relaxed);
+        self.latest.lock().unwrap_or_log().insert(user_id, ticket);
+
+        tokio::time::sleep(DEBOUNCE).await;
+
+        let mut latest = self.latest.lock().unwrap_or_log();
+        if latest.get(&user_id) == Some(&ticket) {
+            latest.remove(&user_id);
+            true
+        } else {
+            false
+        }
+    }
+}
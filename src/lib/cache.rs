@@ -0,0 +1,183 @@
+use std::{
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use lru::LruCache;
+use serde_json::Value;
+use sqlx::{Pool, Postgres};
+use tracing::{event, Level};
+use tracing_unwrap::{OptionExt, ResultExt};
+
+/// How many AniList responses are kept in memory
+const MEMORY_ENTRIES: usize = 1024;
+
+/// TTL cache for AniList responses, so repeat lookups don't count against AniList's rate limit.
+///
+/// Entries live in an in-memory LRU and, if a database pool is given, in the `anilist_cache` table so they survive restarts.
+#[derive(Debug)]
+pub struct ResponseCache {
+    memory: Mutex<LruCache<String, (Instant, Value)>>,
+    db: Option<Pool<Postgres>>,
+    ttl: Duration,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// Snapshot of how well the cache is doing.
+#[derive(Debug, Clone, Copy)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+impl ResponseCache {
+    /// Creates a cache that keeps responses for `ttl`, persisting them to Postgres when `db` is set.
+    pub fn new(ttl: Duration, db: Option<Pool<Postgres>>) -> Self {
+        Self {
+            memory: Mutex::new(LruCache::new(
+                NonZeroUsize::new(MEMORY_ENTRIES).unwrap_or_log(),
+            )),
+            db,
+            ttl,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the cached `data` for `key` if it hasn't expired, checking memory first and then Postgres.
+    pub async fn get(&self, key: &str) -> Option<Value> {
+        let in_memory = self
+            .memory
+            .lock()
+            .unwrap_or_log()
+            .get(key)
+            .filter(|(stored, _)| stored.elapsed() < self.ttl)
+            .map(|(_, data)| data.clone());
+
+        if let Some(data) = in_memory {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Some(data);
+        }
+
+        if let Some(data) = self.get_persisted(key).await {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            self.memory
+                .lock()
+                .unwrap_or_log()
+                .put(key.to_string(), (Instant::now(), data.clone()));
+            return Some(data);
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        None
+    }
+
+    pub async fn insert(&self, key: String, data: Value) {
+        if let Some(db) = &self.db {
+            let result = sqlx::query(
+                "INSERT INTO anilist_cache (key, response) VALUES ($1, $2)
+                ON CONFLICT (key) DO UPDATE SET response = EXCLUDED.response, fetched_at = now();",
+            )
+            .bind(&key)
+            .bind(data.to_string())
+            .execute(db)
+            .await;
+
+            // A broken cache table shouldn't stop lookups from working
+            if let Err(why) = result {
+                event!(Level::WARN, "Failed to persist AniList response." = %why);
+            }
+        }
+
+        self.memory
+            .lock()
+            .unwrap_or_log()
+            .put(key, (Instant::now(), data));
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.memory.lock().unwrap_or_log().len(),
+        }
+    }
+
+    /// Deletes persisted responses that have outlived the TTL, returning how many were removed.
+    ///
+    /// Expired rows are never read again, so without this the `anilist_cache` table only ever grows.
+    pub async fn prune_expired(&self) -> Result<u64, sqlx::Error> {
+        let db = if let Some(db) = &self.db {
+            db
+        } else {
+            return Ok(0);
+        };
+
+        let result = sqlx::query(
+            "DELETE FROM anilist_cache WHERE fetched_at < now() - make_interval(secs => $1);",
+        )
+        .bind(self.ttl.as_secs_f64())
+        .execute(db)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn get_persisted(&self, key: &str) -> Option<Value> {
+        let db = self.db.as_ref()?;
+
+        let row: Result<Option<(String,)>, sqlx::Error> = sqlx::query_as(
+            "SELECT response FROM anilist_cache WHERE (key) = ($1) AND fetched_at > now() - make_interval(secs => $2) LIMIT 1;",
+        )
+        .bind(key)
+        .bind(self.ttl.as_secs_f64())
+        .fetch_optional(db)
+        .await;
+
+        match row {
+            Ok(row) => row.and_then(|(response,)| serde_json::from_str(&response).ok()),
+            Err(why) => {
+                event!(Level::WARN, "Failed to read persisted AniList response." = %why);
+                None
+            }
+        }
+    }
+}
+
+/// Builds the cache key for a query, the search text is normalised so "Bebop" and " bebop " share an entry.
+///
+/// ANIME and MANGA are hard-coded into the queries in `vars.rs`, so the query fingerprint also covers the media type.
+pub fn cache_key(query: &str, variables: &Value) -> String {
+    let mut variables = variables.clone();
+
+    if let Some(search) = variables.get_mut("search") {
+        let normalised = search.as_str().map(|text| {
+            text.split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase()
+        });
+
+        if let Some(normalised) = normalised {
+            *search = Value::String(normalised);
+        }
+    }
+
+    format!("{:016x}:{variables}", fingerprint(query))
+}
+
+/// FNV-1a hash of the query with whitespace ignored, stable between builds unlike `DefaultHasher` so persisted keys stay valid.
+fn fingerprint(query: &str) -> u64 {
+    query
+        .split_whitespace()
+        .flat_map(str::bytes)
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
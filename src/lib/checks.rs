@@ -4,33 +4,74 @@ use sqlx::{Pool, Postgres};
 use tracing::{event, Level};
 use tracing_unwrap::ResultExt;
 
-use crate::structs::UserRow;
+use crate::structs::{GuildSettingsRow, UserRow};
+
+/// How a guild wants adult AniList entries handled, stored in `guild_settings.adult_content`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum AdultContent {
+    /// Only show them in age-restricted channels, the default
+    #[name = "Age-restricted channels only"]
+    Channel,
+    #[name = "Allow everywhere"]
+    Allow,
+    #[name = "Block everywhere"]
+    Block,
+}
+
+impl AdultContent {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Channel => "channel",
+            Self::Allow => "allow",
+            Self::Block => "block",
+        }
+    }
+
+    /// Unknown values fall back to the default rather than failing the command.
+    pub fn from_setting(setting: &str) -> Self {
+        match setting {
+            "allow" => Self::Allow,
+            "block" => Self::Block,
+            _ => Self::Channel,
+        }
+    }
+}
 
 /// This code adds a user to the `users` table in the database if they are not already in the table.
 ///
 /// Note that the `users` table has a column named `id` which is of type `text` and not `bigint`.
 pub async fn user_db_check(db: Pool<Postgres>, user: User) {
+    let added = ensure_user_row(&db, &user.id.0.to_string())
+        .await
+        .expect_or_log("Failed to add user to db");
+
+    if added {
+        let user_info = format!("ID: {} || Current Useranme: {}", user.id.0, user.name);
+
+        event!(Level::INFO, "Added new user to `users` db." = user_info);
+    }
+}
+
+/// Creates a `users` row for `user_id` if there isn't one yet, returning whether it was created.
+pub async fn ensure_user_row(db: &Pool<Postgres>, user_id: &str) -> Result<bool, Error> {
     // Check if user already exists in database
     let optional_user: Option<UserRow> =
         sqlx::query_as("SELECT * FROM users WHERE (id) = ($1) LIMIT 1;")
-            .bind(user.id.0.to_string())
-            .fetch_optional(&db)
-            .await
-            .expect_or_log("Failed to select user from db");
+            .bind(user_id)
+            .fetch_optional(db)
+            .await?;
 
-    // If user does not exist, create a new row in the database
     if optional_user.is_some() {
-    } else {
-        let _row: UserRow = sqlx::query_as("INSERT INTO users (id) VALUES ($1) RETURNING *;")
-            .bind(&user.id.0.to_string())
-            .fetch_one(&db)
-            .await
-            .expect_or_log("Failed to add user to db");
+        return Ok(false);
+    }
 
-        let user_info = format!("ID: {} || Current Useranme: {}", user.id.0, user.name);
+    // If user does not exist, create a new row in the database
+    let _row: UserRow = sqlx::query_as("INSERT INTO users (id) VALUES ($1) RETURNING *;")
+        .bind(user_id)
+        .fetch_one(db)
+        .await?;
 
-        event!(Level::INFO, "Added new user to `users` db." = user_info);
-    }
+    Ok(true)
 }
 
 // is_admin checks if a user is in the `users` table and than if they have the `is_admin` column set to `true`.
@@ -65,3 +106,28 @@ pub async fn is_admin(ctx: Context<'_>) -> Result<bool, Error> {
 
     Ok(false)
 }
+
+/// Whether adult AniList entries (`isAdult`) can be shown where the command was run.
+///
+/// DMs always allow them, guilds follow their `guild_settings` override and otherwise only allow them in age-restricted channels.
+pub async fn adult_content_allowed(ctx: Context<'_>) -> Result<bool, Error> {
+    let guild_id = if let Some(guild_id) = ctx.guild_id() {
+        guild_id
+    } else {
+        return Ok(true);
+    };
+
+    let settings: Option<GuildSettingsRow> =
+        sqlx::query_as("SELECT * FROM guild_settings WHERE (guild_id) = ($1) LIMIT 1;")
+            .bind(guild_id.0.to_string())
+            .fetch_optional(&ctx.data().db.clone())
+            .await?;
+
+    match settings.map_or(AdultContent::Channel, |s| {
+        AdultContent::from_setting(&s.adult_content)
+    }) {
+        AdultContent::Allow => Ok(true),
+        AdultContent::Block => Ok(false),
+        AdultContent::Channel => Ok(ctx.channel_id().to_channel(ctx).await?.is_nsfw()),
+    }
+}
@@ -0,0 +1,157 @@
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::types::Error;
+
+/// File formats data can be exported in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum ExportFormat {
+    #[name = "JSON"]
+    Json,
+    #[name = "YAML"]
+    Yaml,
+    /// Nested fields are flattened into `parent.child` columns so the result opens in a spreadsheet
+    #[name = "CSV"]
+    Csv,
+}
+
+impl ExportFormat {
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Yaml => "yaml",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+/// Serializes `rows` in `format`.
+///
+/// JSON and YAML keep the structure as is, CSV writes a header of flattened field names and one line per row.
+pub fn export_rows<T: Serialize>(rows: &[T], format: ExportFormat) -> Result<String, Error> {
+    match format {
+        ExportFormat::Json => Ok(serde_json::to_string_pretty(rows)?),
+        ExportFormat::Yaml => Ok(serde_yaml::to_string(rows)?),
+        ExportFormat::Csv => {
+            let flattened = rows
+                .iter()
+                .map(|row| Ok(flatten(&serde_json::to_value(row)?)))
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            // Rows can be missing nested fields that others have, so the header is every column seen in order
+            let mut header: Vec<String> = Vec::new();
+            for (column, _) in flattened.iter().flatten() {
+                if !header.contains(column) {
+                    header.push(column.clone());
+                }
+            }
+
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            writer.write_record(&header)?;
+            for row in &flattened {
+                writer.write_record(header.iter().map(|column| {
+                    row.iter()
+                        .find(|(c, _)| c == column)
+                        .map_or("", |(_, value)| value.as_str())
+                }))?;
+            }
+
+            let bytes = writer.into_inner().map_err(|why| why.to_string())?;
+            Ok(String::from_utf8(bytes)?)
+        }
+    }
+}
+
+/// Serializes a single value in `format`, for JSON and YAML it isn't wrapped in a list.
+pub fn export_one<T: Serialize>(value: &T, format: ExportFormat) -> Result<String, Error> {
+    match format {
+        ExportFormat::Json => Ok(serde_json::to_string_pretty(value)?),
+        ExportFormat::Yaml => Ok(serde_yaml::to_string(value)?),
+        ExportFormat::Csv => export_rows(std::slice::from_ref(value), format),
+    }
+}
+
+/// Parses a list of `T` written in `format`, with one result per row so a bad row doesn't stop the rest.
+///
+/// Only fails outright when the file itself can't be read, like JSON that isn't a list. A leading byte order mark is ignored.
+pub fn import_rows<T: DeserializeOwned>(
+    data: &str,
+    format: ExportFormat,
+) -> Result<Vec<Result<T, String>>, Error> {
+    // Spreadsheet programs like to start CSV files with a byte order mark, which would end up in the first header
+    let data = data.strip_prefix('\u{feff}').unwrap_or(data);
+
+    match format {
+        ExportFormat::Json => {
+            let rows: Vec<Value> = serde_json::from_str(data)?;
+            Ok(rows
+                .into_iter()
+                .map(|row| serde_json::from_value(row).map_err(|why| why.to_string()))
+                .collect())
+        }
+        ExportFormat::Yaml => {
+            let rows: Vec<serde_yaml::Value> = serde_yaml::from_str(data)?;
+            Ok(rows
+                .into_iter()
+                .map(|row| serde_yaml::from_value(row).map_err(|why| why.to_string()))
+                .collect())
+        }
+        ExportFormat::Csv => {
+            let mut reader = csv::Reader::from_reader(data.as_bytes());
+            Ok(reader
+                .deserialize()
+                .map(|row| row.map_err(|why| why.to_string()))
+                .collect())
+        }
+    }
+}
+
+/// Flattens nested objects into `parent.child` keys, lists of plain values are joined with `; `.
+///
+/// Lists of objects get their index as a key part, like `nodes.0.id`.
+pub fn flatten(value: &Value) -> Vec<(String, String)> {
+    let mut columns = Vec::new();
+    flatten_into(value, String::new(), &mut columns);
+    columns
+}
+
+fn flatten_into(value: &Value, prefix: String, columns: &mut Vec<(String, String)>) {
+    let key = |name: &str| {
+        if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{prefix}.{name}")
+        }
+    };
+
+    match value {
+        Value::Object(map) => {
+            for (name, value) in map {
+                flatten_into(value, key(name), columns);
+            }
+        }
+        Value::Array(items)
+            if items
+                .iter()
+                .all(|item| !item.is_object() && !item.is_array()) =>
+        {
+            let joined: Vec<String> = items.iter().map(plain).collect();
+            columns.push((prefix, joined.join("; ")));
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                flatten_into(item, key(&index.to_string()), columns);
+            }
+        }
+        _ => columns.push((prefix, plain(value))),
+    }
+}
+
+/// A scalar as it should appear in a cell, strings without their quotes and null as empty.
+fn plain(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
@@ -1,4 +1,10 @@
+pub mod airing;
+pub mod anilist;
+pub mod autocomplete;
+pub mod cache;
 pub mod checks;
+pub mod export;
+pub mod ratelimit;
 pub mod structs;
 pub mod types;
 pub mod utils;
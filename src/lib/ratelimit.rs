@@ -0,0 +1,117 @@
+use std::{
+    fmt,
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use reqwest::header::HeaderMap;
+use tracing::{event, Level};
+use tracing_unwrap::ResultExt;
+
+/// How many times a request is tried before giving up
+pub const MAX_ATTEMPTS: u32 = 3;
+
+/// Longest we'll hold a command up waiting for the rate limit to clear, anything longer is handed back to the user
+const MAX_WAIT: Duration = Duration::from_secs(5);
+
+/// First retry delay for network and server errors, doubled on every attempt
+const BASE_BACKOFF: Duration = Duration::from_millis(250);
+
+/// Used when AniList sends a 429 without a `Retry-After` header, their limit resets every minute
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Returned when AniList is rate limiting us for longer than it's worth waiting.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimited {
+    pub retry_after: Duration,
+}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "AniList is rate limiting requests, try again in {} seconds",
+            self.retry_after.as_secs().max(1)
+        )
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+/// Tracks AniList's rate limit headers so requests are held back instead of being sent just to get a 429.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    blocked_until: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    /// Waits out a short block, or returns [`RateLimited`] straight away if it's longer than `MAX_WAIT`.
+    pub async fn wait(&self) -> Result<(), RateLimited> {
+        let remaining = self
+            .blocked_until
+            .lock()
+            .unwrap_or_log()
+            .and_then(|until| until.checked_duration_since(Instant::now()));
+
+        match remaining {
+            Some(retry_after) if retry_after > MAX_WAIT => Err(RateLimited { retry_after }),
+            Some(wait) => {
+                tokio::time::sleep(wait).await;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Holds back every request for `duration`.
+    pub fn block_for(&self, duration: Duration) {
+        let until = Instant::now() + duration;
+        let mut blocked_until = self.blocked_until.lock().unwrap_or_log();
+
+        if blocked_until.is_none_or(|current| current < until) {
+            *blocked_until = Some(until);
+        }
+    }
+
+    /// Reads `X-RateLimit-Remaining`/`X-RateLimit-Reset` and blocks until the reset once we've run out.
+    pub fn update(&self, headers: &HeaderMap) {
+        let remaining = header_u64(headers, "X-RateLimit-Remaining");
+        let reset = header_u64(headers, "X-RateLimit-Reset");
+
+        if let (Some(0), Some(reset)) = (remaining, reset) {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            let wait = Duration::from_secs(reset.saturating_sub(now));
+
+            event!(
+                Level::WARN,
+                "AniList rate limit used up, holding requests." = wait.as_secs()
+            );
+            self.block_for(wait);
+        }
+    }
+}
+
+/// How long a 429 asked us to wait for.
+pub fn retry_after(headers: &HeaderMap) -> Duration {
+    header_u64(headers, "Retry-After").map_or(DEFAULT_RETRY_AFTER, Duration::from_secs)
+}
+
+/// Whether a request that was told to wait `retry_after` should be tried again rather than reported.
+pub fn should_retry(attempt: u32, retry_after: Duration) -> bool {
+    attempt < MAX_ATTEMPTS && retry_after <= MAX_WAIT
+}
+
+/// Exponential backoff for the given (1 based) attempt.
+pub fn backoff(attempt: u32) -> Duration {
+    BASE_BACKOFF * 2_u32.pow(attempt.saturating_sub(1))
+}
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+}
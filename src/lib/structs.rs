@@ -2,11 +2,91 @@
 pub struct UserRow {
     pub id: String,
     pub is_admin: bool,
+    pub anilist_username: Option<String>,
 }
 
-#[derive(Debug, sqlx::FromRow)]
+#[derive(Debug, sqlx::FromRow, serde::Serialize)]
 pub struct QuoteRow {
     pub id: String,
     pub quote: String,
-    pub author: String,
+    /// The user who ran `addquote`
+    pub added_by: String,
+    /// The Discord user who said the quote
+    pub said_by_id: Option<String>,
+    /// Who said the quote when they aren't a Discord user
+    pub said_by_name: Option<String>,
+    /// When the quote was added, `None` for quotes added before this was recorded
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// When the quoted message was sent, only known for quotes taken from a message
+    pub said_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub channel_id: Option<String>,
+    pub message_id: Option<String>,
+    /// Link to the message the quote was taken from
+    pub jump_url: Option<String>,
+    /// `None` for global quotes, visible in every guild
+    pub guild_id: Option<String>,
+}
+
+/// A quote read from an `importquotes` file, every column but `quote` can be left out.
+///
+/// There's no `guild_id`, imported quotes always belong to the guild importing them.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct QuoteImport {
+    /// Quotes without an ID get a new one, so only rows with an ID are safe to import twice
+    pub id: Option<String>,
+    pub quote: String,
+    pub added_by: Option<String>,
+    pub said_by_id: Option<String>,
+    pub said_by_name: Option<String>,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub said_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub channel_id: Option<String>,
+    pub message_id: Option<String>,
+    pub jump_url: Option<String>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct SubscriptionRow {
+    pub guild_id: String,
+    pub channel_id: String,
+    pub media_id: i64,
+    pub title: String,
+    pub last_episode: i32,
+    pub created_by: String,
+    /// Unix timestamp episodes have been announced up to
+    pub checked_until: i64,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct GuildSettingsRow {
+    pub guild_id: String,
+    pub adult_content: String,
+    pub share_quotes: bool,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct WatchlistRow {
+    pub user_id: String,
+    pub media_id: i64,
+    pub media_type: String,
+    pub title: String,
+    pub status: String,
+    pub progress: i32,
+    pub total: Option<i32>,
+    pub score: Option<i32>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct QuoteTagRow {
+    pub quote_id: String,
+    pub tag: String,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct QuoteSearchRow {
+    pub id: String,
+    pub snippet: String,
+    pub rank: f32,
 }
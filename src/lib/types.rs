@@ -1,6 +1,11 @@
+use crate::{anilist::AniList, autocomplete::TitleAutocomplete};
+
 #[derive(Debug)]
 pub struct Data {
     pub db: sqlx::PgPool,
+    /// Shared AniList client, reuses one `reqwest::Client` for every lookup
+    pub anilist: AniList,
+    pub autocomplete: TitleAutocomplete,
 }
 pub type Error = Box<dyn std::error::Error + Send + Sync>;
 pub type Context<'a> = poise::Context<'a, Data, Error>;
@@ -1,18 +1,24 @@
-use chrono::NaiveDateTime;
+use chrono::DateTime;
 
-use commands::admin::register;
+use commands::admin::{adultcontent, cachestats, register, sharequotes};
 use commands::apis;
 
+use rusted_wumpus_lib::anilist::{AniList, ANILIST_URL};
+use rusted_wumpus_lib::autocomplete::TitleAutocomplete;
+use rusted_wumpus_lib::cache::ResponseCache;
 use rusted_wumpus_lib::checks::user_db_check;
+use rusted_wumpus_lib::ratelimit::RateLimited;
 use rusted_wumpus_lib::types::{Context, Data, Error};
 
 use dotenv::dotenv;
 use sqlx::postgres::PgPoolOptions;
 use tracing::metadata::LevelFilter;
+use tracing::{event, Level};
 use tracing_unwrap::OptionExt;
 // use tracing::{event, Level};
 use std::fs::File;
-use std::time::Instant;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, Layer};
 use tracing_unwrap::ResultExt;
 
@@ -32,7 +38,9 @@ use vars::HELP_EXTRA_TEXT;
 use vars::INFO_MESSAGE;
 
 mod commands;
-use commands::quotes;
+use commands::{airing, quotes, watchlist};
+
+mod scheduler;
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
@@ -49,6 +57,18 @@ pub struct Args {
     /// Discord bot token
     #[clap(short, long, env = "BOT_TOKEN", default_value = "")]
     token: String,
+
+    /// AniList GraphQL endpoint, point this at a mock server for offline testing
+    #[clap(long, env = "ANILIST_URL", default_value = ANILIST_URL)]
+    anilist_url: String,
+
+    /// How many seconds AniList responses are cached for
+    #[clap(long, env = "ANILIST_CACHE_TTL", default_value_t = 600)]
+    anilist_cache_ttl: u64,
+
+    /// Also store cached AniList responses in the `anilist_cache` table so they survive restarts
+    #[clap(long, env = "ANILIST_CACHE_PERSIST")]
+    anilist_cache_persist: bool,
 }
 
 /// Show this help menu
@@ -186,7 +206,7 @@ async fn creationdate(
 ) -> Result<(), Error> {
     let unix_timecode = snowflake_to_unix(snowflake_id);
 
-    let date_time_stamp = NaiveDateTime::from_timestamp_opt(unix_timecode as i64, 0);
+    let date_time_stamp = DateTime::from_timestamp(unix_timecode as i64, 0).map(|d| d.naive_utc());
 
     if date_time_stamp.is_none() {
         ctx.say("Unable to retrieve timestamp from snowflake")
@@ -211,6 +231,22 @@ const fn snowflake_to_unix(id: u128) -> u128 {
     ((id >> 22) + DISCORD_EPOCH) / 1000
 }
 
+/// Replies with a friendly message for errors the user can do something about, anything else goes to poise's default handler
+async fn on_error(error: poise::FrameworkError<'_, Data, Error>) {
+    if let poise::FrameworkError::Command { error: why, ctx } = &error {
+        if let Some(limited) = why.downcast_ref::<RateLimited>() {
+            if let Err(why) = ctx.say(limited.to_string()).await {
+                event!(Level::ERROR, "Failed to send rate limit reply." = %why);
+            }
+            return;
+        }
+    }
+
+    if let Err(why) = poise::builtins::on_error(error).await {
+        event!(Level::ERROR, "Failed to handle command error." = %why);
+    }
+}
+
 // Handle bot start and settings here
 #[tokio::main]
 async fn main() {
@@ -257,7 +293,15 @@ async fn main() {
         .connect(&args.database_url)
         .await
         .expect_or_log("Unable to connect to the DB!");
-    let data = Data { db: db.clone() };
+    let anilist_cache = ResponseCache::new(
+        Duration::from_secs(args.anilist_cache_ttl),
+        args.anilist_cache_persist.then(|| db.clone()),
+    );
+    let data = Data {
+        db: db.clone(),
+        anilist: AniList::new(reqwest::Client::new(), args.anilist_url).with_cache(anilist_cache),
+        autocomplete: TitleAutocomplete::default(),
+    };
 
     // Run migrations automatically when launched to make sure the DB is setup correctly.
     // todo: Make sure this actually sets up from empty databases down the line so no user setup other than the basics of Postgres are needed.
@@ -266,10 +310,17 @@ async fn main() {
         .await
         .expect_or_log("Failed to run migrations");
 
+    // Persisted responses outlive their TTL in the table until they're pruned
+    if args.anilist_cache_persist {
+        tokio::spawn(scheduler::prune_anilist_cache(data.anilist.clone()));
+    }
+
     let mut bot_commands = vec![
         age(),
         help(),
         register(),
+        cachestats(),
+        adultcontent(),
         ping(),
         info(),
         owo(),
@@ -277,6 +328,15 @@ async fn main() {
         pog(),
         apis::anime(),
         apis::manga(),
+        apis::character(),
+        apis::staff(),
+        apis::studio(),
+        apis::season(),
+        apis::related(),
+        apis::compare(),
+        apis::randomanime(),
+        apis::randommanga(),
+        apis::anilist(),
     ];
 
     #[cfg(feature = "testing")]
@@ -291,8 +351,27 @@ async fn main() {
             quotes::addquote(),
             quotes::randquote(),
             quotes::delquote(),
+            quotes::tagquote(),
+            quotes::untagquote(),
+            quotes::listtags(),
+            quotes::searchquote(),
+            quotes::quotesby(),
+            quotes::quotemessage(),
+            quotes::exportquotes(),
+            quotes::importquotes(),
+            quotes::listquotes(),
+            sharequotes(),
+            airing::airing(),
+            watchlist::watchlist(),
         ];
         bot_commands.append(&mut post_features);
+
+        // Episode announcements run alongside the bot, sending through their own HTTP client
+        tokio::spawn(scheduler::announce_airing(
+            Arc::new(serenity::Http::new(&args.token)),
+            db.clone(),
+            data.anilist.clone(),
+        ));
     }
 
     let framework = poise::Framework::builder()
@@ -313,6 +392,7 @@ async fn main() {
                 })
             },
             command_check: Some(|_ctx| Box::pin(async move { Ok(true) })),
+            on_error: |error| Box::pin(on_error(error)),
             ..Default::default()
         });
 
@@ -0,0 +1,211 @@
+use std::{
+    collections::BTreeSet,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use poise::serenity_prelude::{ChannelId, Http, HttpError, SerenityError, Timestamp};
+use rusted_wumpus_lib::{
+    airing::{poll_window, AiringCursor},
+    anilist::{AiringSchedule, AniList},
+    structs::SubscriptionRow,
+    types::Error,
+};
+use sqlx::PgPool;
+use tracing::{event, Level};
+
+use crate::vars::AIRING_SCHEDULE_QUERY;
+
+/// How often AniList is checked for newly aired episodes
+const POLL_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Furthest back a poll looks, so a long outage doesn't announce weeks of old episodes at once
+const MAX_CATCH_UP: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How often expired responses are deleted from the `anilist_cache` table
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Checks AniList for newly aired episodes of subscribed anime forever, posting them to the subscribed channels.
+///
+/// Every subscription remembers how far it has been checked, so a failed poll, a failed announcement or a restart
+/// is caught up on by the next poll.
+pub async fn announce_airing(http: Arc<Http>, db: PgPool, anilist: AniList) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(why) = poll(&http, &db, &anilist, unix_now()).await {
+            event!(Level::WARN, "Failed to check airing schedules." = %why);
+        }
+    }
+}
+
+/// Deletes expired AniList responses from the `anilist_cache` table forever.
+pub async fn prune_anilist_cache(anilist: AniList) {
+    let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let cache = if let Some(cache) = anilist.cache() {
+            cache
+        } else {
+            return;
+        };
+
+        match cache.prune_expired().await {
+            Ok(removed) => event!(Level::DEBUG, "Pruned expired AniList responses." = removed),
+            Err(why) => event!(Level::WARN, "Failed to prune AniList cache." = %why),
+        }
+    }
+}
+
+async fn poll(http: &Http, db: &PgPool, anilist: &AniList, to: i64) -> Result<(), Error> {
+    let subscriptions: Vec<SubscriptionRow> = sqlx::query_as("SELECT * FROM anime_subscriptions;")
+        .fetch_all(db)
+        .await?;
+
+    let (from, to) = if let Some(window) = poll_window(
+        subscriptions.iter().map(|s| s.checked_until),
+        to,
+        MAX_CATCH_UP.as_secs() as i64,
+    ) {
+        window
+    } else {
+        return Ok(());
+    };
+
+    let media_ids: Vec<i64> = subscriptions
+        .iter()
+        .map(|s| s.media_id)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let schedules = anilist
+        .airing_schedules(AIRING_SCHEDULE_QUERY, &media_ids, from, to)
+        .await?;
+
+    for subscription in &subscriptions {
+        let checked_until = announce_new(http, db, subscription, &schedules, to).await?;
+
+        sqlx::query(
+            "UPDATE anime_subscriptions SET checked_until = ($1) WHERE (channel_id, media_id) = ($2, $3);",
+        )
+        .bind(checked_until)
+        .bind(&subscription.channel_id)
+        .bind(subscription.media_id)
+        .execute(db)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Announces the episodes of `subscription` in `schedules`, oldest first, returning how far it got.
+///
+/// That's `to` when everything went out, or the airing time of the first episode that failed so it's retried next poll.
+/// Subscriptions in channels the bot can no longer post in are removed.
+async fn announce_new(
+    http: &Http,
+    db: &PgPool,
+    subscription: &SubscriptionRow,
+    schedules: &[AiringSchedule],
+    to: i64,
+) -> Result<i64, Error> {
+    let channel_id = ChannelId(subscription.channel_id.parse()?);
+    let mut cursor = AiringCursor {
+        checked_until: subscription.checked_until,
+        last_episode: subscription.last_episode,
+    };
+
+    for schedule in cursor.pending(subscription.media_id, schedules) {
+        if let Err(why) = announce(http, channel_id, subscription, schedule).await {
+            if is_gone(&why) {
+                event!(Level::INFO, "Removing subscriptions for a channel the bot can't post in." = %why, channel = %subscription.channel_id);
+                sqlx::query("DELETE FROM anime_subscriptions WHERE (channel_id) = ($1);")
+                    .bind(&subscription.channel_id)
+                    .execute(db)
+                    .await?;
+            } else {
+                event!(Level::WARN, "Failed to announce aired episode." = %why, channel = %subscription.channel_id);
+            }
+
+            cursor.failed(schedule);
+            return Ok(cursor.checked_until);
+        }
+        cursor.announced(schedule);
+
+        sqlx::query(
+            "UPDATE anime_subscriptions SET last_episode = ($1) WHERE (channel_id, media_id) = ($2, $3);",
+        )
+        .bind(cursor.last_episode)
+        .bind(&subscription.channel_id)
+        .bind(subscription.media_id)
+        .execute(db)
+        .await?;
+    }
+
+    cursor.finished(to);
+    Ok(cursor.checked_until)
+}
+
+/// Whether Discord refused a message because the channel is gone (404) or the bot lost access to it (403).
+fn is_gone(why: &SerenityError) -> bool {
+    match why {
+        SerenityError::Http(http) => matches!(
+            http.as_ref(),
+            HttpError::UnsuccessfulRequest(resp) if matches!(resp.status_code.as_u16(), 403 | 404)
+        ),
+        _ => false,
+    }
+}
+
+async fn announce(
+    http: &Http,
+    channel_id: ChannelId,
+    subscription: &SubscriptionRow,
+    schedule: &AiringSchedule,
+) -> Result<(), SerenityError> {
+    let media = schedule.media.as_ref();
+    let title = media.map_or(subscription.title.as_str(), |m| m.display_title());
+
+    channel_id
+        .send_message(http, |m| {
+            m.embed(|e| {
+                e.description(format!(
+                    "Episode {} of {title} just aired",
+                    schedule.episode
+                ))
+                .timestamp(
+                    Timestamp::from_unix_timestamp(schedule.airing_at)
+                        .unwrap_or_else(|_| Timestamp::now()),
+                );
+
+                if let Some(media) = media {
+                    e.colour(media.colour());
+
+                    if let Some(url) = media.site_url.as_deref() {
+                        e.url(url).title(title);
+                    }
+                    if let Some(cover) = media.cover_image.as_ref().and_then(|c| c.large.as_deref())
+                    {
+                        e.thumbnail(cover);
+                    }
+                }
+
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
@@ -11,91 +11,457 @@ I'll keep adding more till the end of time.
 — RustBot 🤖🎉🦀
 ";
 
+// Fields every anime lookup asks for, shared so the queries below can't drift apart
+macro_rules! anime_fields {
+    () => {
+        "      id
+      title {
+        romaji
+        english
+        native
+      }
+      status
+      description
+      startDate {
+          year
+          month
+          day
+      }
+      endDate {
+          year
+          month
+          day
+      }
+      coverImage {
+          extraLarge
+          large
+          color
+      }
+      season
+      seasonYear
+      seasonInt
+      episodes
+      duration
+      hashtag
+      trailer {
+          id
+          site
+          thumbnail
+      }
+      genres
+      averageScore
+      meanScore
+      isAdult
+      externalLinks {
+          url
+          site
+          type
+      }
+      streamingEpisodes {
+          title
+          thumbnail
+          url
+          site
+      }
+      siteUrl
+"
+    };
+}
+
+// Fields every manga lookup asks for, shared so the queries below can't drift apart
+macro_rules! manga_fields {
+    () => {
+        "      id
+      title {
+        romaji
+        english
+        native
+      }
+      status
+      description
+      startDate {
+          year
+          month
+          day
+      }
+      endDate {
+          year
+          month
+          day
+      }
+      coverImage {
+          extraLarge
+          large
+          color
+      }
+      volumes
+      chapters
+      season
+      seasonYear
+      seasonInt
+      hashtag
+      genres
+      averageScore
+      meanScore
+      isAdult
+      siteUrl
+"
+    };
+}
+
+// Query to use in AniList request
+pub const ANIME_QUERY: &str = concat!(
+    "
+query ($id: Int, $search: String, $perPage: Int) { # Define which variables will be used in the query, unset ones are ignored by AniList
+  Page (perPage: $perPage) { # Return a page of candidates instead of just the single best match
+    media (id: $id, search: $search, type: ANIME) { # Insert our variables into the query arguments (type: ANIME is hard-coded in the query)
+",
+    anime_fields!(),
+    "    }
+  }
+}
+"
+);
+
+// Query to use in AniList request
+pub const MANGA_QUERY: &str = concat!(
+    "
+query ($id: Int, $search: String, $perPage: Int) { # Define which variables will be used in the query, unset ones are ignored by AniList
+  Page (perPage: $perPage) { # Return a page of candidates instead of just the single best match
+    media (id: $id, search: $search, type: MANGA) { # Insert our variables into the query arguments (type: MANGA is hard-coded in the query)
+",
+    manga_fields!(),
+    "    }
+  }
+}
+"
+);
+
 // Query to use in AniList request
-pub const ANIME_QUERY: &str = "
-query ($search: String) { # Define which variables will be used in the query (id)
-  Media (search: $search, type: ANIME) { # Insert our variables into the query arguments (id) (type: ANIME is hard-coded in the query)
+pub const CHARACTER_QUERY: &str = "
+query ($search: String) {
+  Character (search: $search) {
     id
-    title {
-      romaji
-      english
+    name {
+      full
       native
     }
-    status
+    image {
+      large
+      medium
+    }
     description
-    startDate {
-        year
-        month
-        day
-    }
-    endDate {
-        year
-        month
-        day
-    }
-    coverImage {
-        extraLarge
-        large
-        color
-    }
-    season
-    seasonYear
-    seasonInt
-    episodes
-    duration
-    hashtag
-    trailer {
+    favourites
+    siteUrl
+    media (sort: POPULARITY_DESC, perPage: 5) { # Top appearances
+      nodes {
         id
-        site
-        thumbnail
+        title {
+          romaji
+          english
+        }
+        type
+        isAdult
+        siteUrl
+      }
     }
-    genres
-    averageScore
-    meanScore
-    isAdult
-    siteUrl
   }
 }
 ";
 
 // Query to use in AniList request
-pub const MANGA_QUERY: &str = "
-query ($search: String) { # Define which variables will be used in the query (id)
-  Media (search: $search, type: MANGA) { # Insert our variables into the query arguments (id) (type: MANGA is hard-coded in the query)
+pub const STAFF_QUERY: &str = "
+query ($search: String) {
+  Staff (search: $search) {
     id
-    title {
-      romaji
-      english
+    name {
+      full
       native
     }
-    status
+    image {
+      large
+      medium
+    }
     description
-    startDate {
-        year
-        month
-        day
-    }
-    endDate {
-        year
-        month
-        day
-    }
-    coverImage {
-        extraLarge
-        large
-        color
-    }
-    volumes
-    chapters
-    season
-    seasonYear
-    seasonInt
-    hashtag
-    genres
-    averageScore
-    meanScore
-    isAdult
+    primaryOccupations
+    favourites
+    siteUrl
+    staffMedia (sort: POPULARITY_DESC, perPage: 5) { # Top works
+      nodes {
+        id
+        title {
+          romaji
+          english
+        }
+        type
+        isAdult
+        siteUrl
+      }
+    }
+  }
+}
+";
+
+// Query to use in AniList request, studios don't have an image or description on AniList
+pub const STUDIO_QUERY: &str = "
+query ($search: String) {
+  Studio (search: $search) {
+    id
+    name
+    isAnimationStudio
+    favourites
+    siteUrl
+    media (sort: POPULARITY_DESC, perPage: 5, isMain: true) { # Top productions
+      nodes {
+        id
+        title {
+          romaji
+          english
+        }
+        type
+        coverImage {
+          extraLarge
+          large
+          color
+        }
+        isAdult
+        siteUrl
+      }
+    }
+  }
+}
+";
+
+// Query to use in AniList request
+pub const USER_QUERY: &str = "
+query ($name: String) {
+  User (name: $name) {
+    id
+    name
     siteUrl
+    avatar {
+      large
+      medium
+    }
+    options {
+      profileColor
+    }
+    statistics {
+      anime {
+        count
+        meanScore
+        minutesWatched
+        episodesWatched
+        genres (limit: 5, sort: COUNT_DESC) {
+          genre
+          count
+        }
+      }
+      manga {
+        count
+        meanScore
+        chaptersRead
+        volumesRead
+        genres (limit: 5, sort: COUNT_DESC) {
+          genre
+          count
+        }
+      }
+    }
+  }
+}
+";
+
+// Query to use in AniList request, needs the user's ID from `USER_QUERY`
+pub const USER_ACTIVITY_QUERY: &str = "
+query ($userId: Int) {
+  Page (perPage: 5) {
+    activities (userId: $userId, type: MEDIA_LIST, sort: ID_DESC) {
+      ... on ListActivity {
+        status
+        progress
+        createdAt
+        media {
+          id
+          title {
+            romaji
+            english
+          }
+          type
+          siteUrl
+        }
+      }
+    }
+  }
+}
+";
+
+// Query used by the related command, relations and recommendations are only requested with enough to list and re-fetch them
+pub const RELATED_QUERY: &str = "
+query ($id: Int, $search: String, $type: MediaType) {
+  Page (perPage: 1) {
+    media (id: $id, search: $search, type: $type) {
+      id
+      title {
+        romaji
+        english
+      }
+      type
+      format
+      coverImage {
+          large
+          color
+      }
+      isAdult
+      siteUrl
+      relations {
+        edges {
+          relationType
+          node {
+            id
+            title {
+              romaji
+              english
+            }
+            type
+            format
+            isAdult
+            siteUrl
+          }
+        }
+      }
+      recommendations (sort: RATING_DESC, perPage: 10) {
+        nodes {
+          rating
+          mediaRecommendation {
+            id
+            title {
+              romaji
+              english
+            }
+            type
+            format
+            isAdult
+            siteUrl
+          }
+        }
+      }
+    }
+  }
+}
+";
+
+// Query used by randomanime, unset filters are ignored by AniList
+pub const RANDOM_ANIME_QUERY: &str = concat!(
+    "
+query ($page: Int, $perPage: Int, $genres: [String], $minScore: Int, $format: MediaFormat, $startFrom: FuzzyDateInt, $startTo: FuzzyDateInt, $isAdult: Boolean) {
+  Page (page: $page, perPage: $perPage) {
+    pageInfo {
+      total
+    }
+    media (type: ANIME, genre_in: $genres, averageScore_greater: $minScore, format: $format, startDate_greater: $startFrom, startDate_lesser: $startTo, isAdult: $isAdult, sort: ID) {
+",
+    anime_fields!(),
+    "    }
+  }
+}
+"
+);
+
+// Query used by randommanga, unset filters are ignored by AniList
+pub const RANDOM_MANGA_QUERY: &str = concat!(
+    "
+query ($page: Int, $perPage: Int, $genres: [String], $minScore: Int, $format: MediaFormat, $startFrom: FuzzyDateInt, $startTo: FuzzyDateInt, $isAdult: Boolean) {
+  Page (page: $page, perPage: $perPage) {
+    pageInfo {
+      total
+    }
+    media (type: MANGA, genre_in: $genres, averageScore_greater: $minScore, format: $format, startDate_greater: $startFrom, startDate_lesser: $startTo, isAdult: $isAdult, sort: ID) {
+",
+    manga_fields!(),
+    "    }
+  }
+}
+"
+);
+
+// Query used for the seasonal chart, only what's shown in the listing is requested
+pub const SEASON_QUERY: &str = "
+query ($season: MediaSeason, $seasonYear: Int, $sort: [MediaSort], $page: Int) {
+  Page (page: $page, perPage: 50) {
+    pageInfo {
+      hasNextPage
+    }
+    media (season: $season, seasonYear: $seasonYear, sort: $sort, type: ANIME) {
+      id
+      title {
+        romaji
+        english
+      }
+      format
+      status
+      startDate {
+          year
+          month
+          day
+      }
+      coverImage {
+          large
+          color
+      }
+      season
+      seasonYear
+      episodes
+      averageScore
+      popularity
+      isAdult
+      siteUrl
+    }
+  }
+}
+";
+
+// Query used by the airing scheduler, finds episodes of subscribed shows that aired in a time window
+pub const AIRING_SCHEDULE_QUERY: &str = "
+query ($mediaIds: [Int], $from: Int, $to: Int, $page: Int) {
+  Page (page: $page, perPage: 50) {
+    pageInfo {
+      hasNextPage
+    }
+    airingSchedules (mediaId_in: $mediaIds, airingAt_greater: $from, airingAt_lesser: $to, sort: TIME) {
+      id
+      episode
+      airingAt
+      mediaId
+      media {
+        id
+        title {
+          romaji
+          english
+        }
+        coverImage {
+          large
+          color
+        }
+        siteUrl
+      }
+    }
+  }
+}
+";
+
+// Lightweight query used for slash command autocomplete, only fetches what the suggestions need
+pub const TITLE_SEARCH_QUERY: &str = "
+query ($search: String, $type: MediaType) {
+  Page (perPage: 25) { # Discord shows at most 25 autocomplete choices
+    media (search: $search, type: $type) {
+      id
+      title {
+        romaji
+        english
+      }
+      isAdult
+    }
   }
 }
 ";
@@ -0,0 +1,114 @@
+#[allow(dead_code)]
+mod common;
+
+use std::collections::HashMap;
+
+use common::{MockAniList, MockResponse};
+use rusted_wumpus_lib::{
+    airing::{poll_window, AiringCursor},
+    anilist::AniList,
+};
+
+#[allow(dead_code)]
+#[path = "../src/vars.rs"]
+mod vars;
+
+use vars::AIRING_SCHEDULE_QUERY;
+
+const ONE_PIECE: i64 = 21;
+
+async fn server() -> MockAniList {
+    MockAniList::start(HashMap::from([(
+        "airing",
+        MockResponse::ok(include_str!("fixtures/anilist/airing_schedules.json")),
+    )]))
+    .await
+}
+
+/// One scheduler poll for a single subscription, announcing everything but the episodes in `failing`.
+///
+/// Returns the episodes that were announced.
+async fn poll(anilist: &AniList, cursor: &mut AiringCursor, now: i64, failing: &[i32]) -> Vec<i32> {
+    let (from, to) = poll_window([cursor.checked_until], now, 86_400).unwrap();
+    let schedules = anilist
+        .airing_schedules(AIRING_SCHEDULE_QUERY, &[ONE_PIECE], from, to)
+        .await
+        .unwrap();
+
+    let mut announced = Vec::new();
+    for schedule in cursor.pending(ONE_PIECE, &schedules) {
+        if failing.contains(&schedule.episode) {
+            cursor.failed(schedule);
+            return announced;
+        }
+        cursor.announced(schedule);
+        announced.push(schedule.episode);
+    }
+    cursor.finished(to);
+
+    announced
+}
+
+#[tokio::test]
+async fn failed_episodes_are_retried_once() {
+    let server = server().await;
+    let anilist = AniList::new(reqwest::Client::new(), server.url.clone());
+    let mut cursor = AiringCursor {
+        checked_until: 500,
+        last_episode: 0,
+    };
+
+    assert_eq!(poll(&anilist, &mut cursor, 2500, &[2]).await, vec![1]);
+    assert_eq!(
+        cursor,
+        AiringCursor {
+            checked_until: 2000,
+            last_episode: 1
+        }
+    );
+
+    assert_eq!(poll(&anilist, &mut cursor, 2600, &[]).await, vec![2]);
+    assert_eq!(poll(&anilist, &mut cursor, 3500, &[]).await, vec![3]);
+    assert!(poll(&anilist, &mut cursor, 4000, &[]).await.is_empty());
+}
+
+#[tokio::test]
+async fn episodes_on_the_window_edge_arent_skipped_or_repeated() {
+    let server = server().await;
+    let anilist = AniList::new(reqwest::Client::new(), server.url.clone());
+    let mut cursor = AiringCursor {
+        checked_until: 500,
+        last_episode: 0,
+    };
+
+    // Episode 2 airs exactly when this poll runs, so it belongs to the next one
+    assert_eq!(poll(&anilist, &mut cursor, 2000, &[]).await, vec![1]);
+    assert_eq!(poll(&anilist, &mut cursor, 2001, &[]).await, vec![2]);
+    assert!(poll(&anilist, &mut cursor, 2002, &[]).await.is_empty());
+}
+
+#[tokio::test]
+async fn overlapping_windows_dont_repeat_episodes() {
+    let server = server().await;
+    let anilist = AniList::new(reqwest::Client::new(), server.url.clone());
+    let mut cursor = AiringCursor {
+        checked_until: 500,
+        last_episode: 0,
+    };
+
+    assert_eq!(poll(&anilist, &mut cursor, 3500, &[]).await, vec![1, 2, 3]);
+
+    // Another subscription to the same show that's further behind drags the shared window back
+    cursor.checked_until = 500;
+    assert!(poll(&anilist, &mut cursor, 3600, &[]).await.is_empty());
+}
+
+#[test]
+fn catch_up_is_capped() {
+    assert_eq!(poll_window([100, 50], 10_000, 1_000), Some((8_999, 10_000)));
+    assert_eq!(
+        poll_window([9_500, 9_800], 10_000, 1_000),
+        Some((9_499, 10_000))
+    );
+    assert_eq!(poll_window([], 10_000, 1_000), None);
+}
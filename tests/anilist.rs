@@ -0,0 +1,513 @@
+mod common;
+
+use std::{collections::HashMap, time::Duration};
+
+use common::{MockAniList, MockResponse};
+use rusted_wumpus_lib::{
+    anilist::{AniList, Media, MediaFilters, MediaTrailer},
+    cache::ResponseCache,
+    ratelimit::{RateLimited, MAX_ATTEMPTS},
+};
+use serde_json::json;
+
+// The queries the bot ships, so the mock server checks the real strings
+#[allow(dead_code)]
+#[path = "../src/vars.rs"]
+mod vars;
+
+use vars::{
+    ANIME_QUERY, CHARACTER_QUERY, MANGA_QUERY, RELATED_QUERY, SEASON_QUERY, STUDIO_QUERY,
+    USER_QUERY,
+};
+
+fn routes() -> HashMap<&'static str, MockResponse> {
+    HashMap::from([
+        (
+            "Cowboy Bebop",
+            MockResponse::ok(include_str!("fixtures/anilist/anime_cowboy_bebop.json")),
+        ),
+        (
+            "Tsuki ga Kirei",
+            MockResponse::ok(include_str!("fixtures/anilist/anime_missing_title.json")),
+        ),
+        (
+            "One Piece",
+            MockResponse::ok(include_str!("fixtures/anilist/anime_airing.json")),
+        ),
+        (
+            "Adult",
+            MockResponse::ok(include_str!("fixtures/anilist/anime_adult.json")),
+        ),
+        (
+            "Berserk",
+            MockResponse::ok(include_str!("fixtures/anilist/manga_berserk.json")),
+        ),
+        (
+            "Bebop",
+            MockResponse::ok(include_str!("fixtures/anilist/page_cowboy_bebop.json")),
+        ),
+        (
+            "id:5",
+            MockResponse::ok(include_str!("fixtures/anilist/page_id_5.json")),
+        ),
+        (
+            "id:404",
+            MockResponse::ok(include_str!("fixtures/anilist/page_empty.json")),
+        ),
+        (
+            "Nothing",
+            MockResponse::ok(include_str!("fixtures/anilist/page_empty.json")),
+        ),
+        (
+            "Broken",
+            MockResponse::status(500, "<html>Internal Server Error</html>"),
+        ),
+        ("Rate Limited", MockResponse::rate_limited(60)),
+        (
+            "Spike",
+            MockResponse::ok(include_str!("fixtures/anilist/character_spike.json")),
+        ),
+        (
+            "MockUser",
+            MockResponse::ok(include_str!("fixtures/anilist/user_profile.json")),
+        ),
+        (
+            "Sunrise",
+            MockResponse::ok(include_str!("fixtures/anilist/studio_sunrise.json")),
+        ),
+        (
+            "Bebop Relations",
+            MockResponse::ok(include_str!("fixtures/anilist/related_cowboy_bebop.json")),
+        ),
+        (
+            "season:SPRING:1998:1",
+            MockResponse::ok(include_str!(
+                "fixtures/anilist/season_spring_1998_page1.json"
+            )),
+        ),
+        (
+            "season:SPRING:1998:2",
+            MockResponse::ok(include_str!(
+                "fixtures/anilist/season_spring_1998_page2.json"
+            )),
+        ),
+    ])
+}
+
+async fn mock_client() -> AniList {
+    let server = MockAniList::start(routes()).await;
+    AniList::new(reqwest::Client::new(), server.url)
+}
+
+/// The best match for `search`, the way the anime and manga commands look entries up.
+async fn first_match(anilist: &AniList, query: &str, search: &str) -> Media {
+    anilist
+        .search_media_page(query, search, 1)
+        .await
+        .unwrap()
+        .into_iter()
+        .next()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn finds_anime() {
+    let anilist = mock_client().await;
+
+    let media = first_match(&anilist, ANIME_QUERY, "Cowboy Bebop").await;
+
+    assert_eq!(media.id, 1);
+    assert_eq!(media.display_title(), "Cowboy Bebop");
+    assert_eq!(media.episodes, Some(26));
+    assert_eq!(media.colour(), 0x00f1_785d);
+    assert_eq!(media.start_date.unwrap().to_string(), "1998/4/3");
+}
+
+#[tokio::test]
+async fn missing_english_title_falls_back_to_romaji() {
+    let anilist = mock_client().await;
+
+    let media = first_match(&anilist, ANIME_QUERY, "Tsuki ga Kirei").await;
+
+    assert_eq!(media.title.english, None);
+    assert_eq!(media.display_title(), "Tsuki ga Kirei");
+    assert_eq!(media.description, None);
+    // No cover colour means the default light blue
+    assert_eq!(media.colour(), 0x00ae_d6f1);
+}
+
+#[tokio::test]
+async fn airing_anime_has_null_dates_and_episodes() {
+    let anilist = mock_client().await;
+
+    let media = first_match(&anilist, ANIME_QUERY, "One Piece").await;
+
+    assert_eq!(media.episodes, None);
+    assert_eq!(media.end_date.unwrap().to_string(), "?/?/?");
+}
+
+#[tokio::test]
+async fn adult_entries_are_flagged() {
+    let anilist = mock_client().await;
+
+    let media = first_match(&anilist, ANIME_QUERY, "Adult").await;
+
+    assert_eq!(media.is_adult, Some(true));
+    assert_eq!(media.average_score, None);
+}
+
+#[tokio::test]
+async fn finds_manga_with_unknown_counts() {
+    let anilist = mock_client().await;
+
+    let media = first_match(&anilist, MANGA_QUERY, "Berserk").await;
+
+    assert_eq!(media.volumes, None);
+    assert_eq!(media.chapters, None);
+    assert_eq!(media.episodes, None);
+}
+
+#[tokio::test]
+async fn no_match_is_none() {
+    let anilist = mock_client().await;
+
+    let media = anilist.media_by_id(ANIME_QUERY, 404).await.unwrap();
+
+    assert!(media.is_none());
+}
+
+#[tokio::test]
+async fn http_errors_are_returned() {
+    let anilist = mock_client().await;
+
+    let why = anilist
+        .search_media_page(ANIME_QUERY, "Broken", 1)
+        .await
+        .unwrap_err();
+
+    assert!(why.to_string().contains("500"));
+}
+
+#[tokio::test]
+async fn page_search_returns_every_candidate() {
+    let anilist = mock_client().await;
+
+    let results = anilist
+        .search_media_page(ANIME_QUERY, "Bebop", 10)
+        .await
+        .unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].id, 1);
+    assert_eq!(results[1].display_title(), "Cowboy Bebop: The Movie");
+}
+
+#[tokio::test]
+async fn empty_page_is_empty() {
+    let anilist = mock_client().await;
+
+    let results = anilist
+        .search_media_page(ANIME_QUERY, "Nothing", 10)
+        .await
+        .unwrap();
+
+    assert!(results.is_empty());
+}
+
+#[tokio::test]
+async fn lookup_by_id() {
+    let anilist = mock_client().await;
+
+    let media = anilist.media_by_id(ANIME_QUERY, 5).await.unwrap().unwrap();
+
+    // The movie is second in the "Bebop" search, so this only passes if the ID is what was looked up
+    assert_eq!(media.id, 5);
+    assert_eq!(media.display_title(), "Cowboy Bebop: The Movie");
+}
+
+#[tokio::test]
+async fn raw_lookup_only_has_requested_fields() {
+    let anilist = mock_client().await;
+
+    let raw = anilist
+        .raw_media_by_id(ANIME_QUERY, 5)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(raw["id"], 5);
+    assert_eq!(raw["title"]["english"], "Cowboy Bebop: The Movie");
+    assert!(raw.get("chapters").is_none());
+}
+
+#[tokio::test]
+async fn cache_answers_repeat_searches() {
+    let server = MockAniList::start(routes()).await;
+    let anilist = AniList::new(reqwest::Client::new(), server.url.clone())
+        .with_cache(ResponseCache::new(Duration::from_secs(60), None));
+
+    let first = anilist
+        .search_media_page(ANIME_QUERY, "Bebop", 10)
+        .await
+        .unwrap();
+    // Differently spaced and cased searches share a cache entry
+    let second = anilist
+        .search_media_page(ANIME_QUERY, "  BEBOP ", 10)
+        .await
+        .unwrap();
+
+    assert_eq!(first.len(), second.len());
+    assert_eq!(server.request_count(), 1);
+
+    let stats = anilist.cache().unwrap().stats();
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.misses, 1);
+}
+
+#[tokio::test]
+async fn errors_are_not_cached() {
+    let server = MockAniList::start(routes()).await;
+    let anilist = AniList::new(reqwest::Client::new(), server.url.clone())
+        .with_cache(ResponseCache::new(Duration::from_secs(60), None));
+
+    assert!(anilist
+        .search_media_page(ANIME_QUERY, "Broken", 1)
+        .await
+        .is_err());
+    assert!(anilist
+        .search_media_page(ANIME_QUERY, "Broken", 1)
+        .await
+        .is_err());
+
+    // Server errors are retried, so each lookup makes every attempt
+    assert_eq!(server.request_count(), 2 * MAX_ATTEMPTS as usize);
+}
+
+#[tokio::test]
+async fn long_rate_limits_are_reported() {
+    let server = MockAniList::start(routes()).await;
+    let anilist = AniList::new(reqwest::Client::new(), server.url.clone());
+
+    let why = anilist
+        .search_media_page(ANIME_QUERY, "Rate Limited", 1)
+        .await
+        .unwrap_err();
+    let limited = why.downcast_ref::<RateLimited>().unwrap();
+
+    assert_eq!(limited.retry_after.as_secs(), 60);
+    assert_eq!(server.request_count(), 1);
+}
+
+#[tokio::test]
+async fn requests_are_held_while_rate_limited() {
+    let server = MockAniList::start(routes()).await;
+    let anilist = AniList::new(reqwest::Client::new(), server.url.clone());
+
+    assert!(anilist
+        .search_media_page(ANIME_QUERY, "Rate Limited", 1)
+        .await
+        .is_err());
+
+    // Even a search that would succeed isn't sent until the limit resets
+    let why = anilist
+        .search_media_page(ANIME_QUERY, "Cowboy Bebop", 1)
+        .await
+        .unwrap_err();
+
+    assert!(why.downcast_ref::<RateLimited>().is_some());
+    assert_eq!(server.request_count(), 1);
+}
+
+#[tokio::test]
+async fn finds_character_with_appearances() {
+    let anilist = mock_client().await;
+
+    let character = anilist
+        .search_character(CHARACTER_QUERY, "Spike")
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(character.name.full.as_deref(), Some("Spike Spiegel"));
+    assert_eq!(character.favourites, Some(46210));
+
+    let appearances = character.media.unwrap().nodes;
+    assert_eq!(appearances.len(), 2);
+    assert_eq!(appearances[1].media_type.as_deref(), Some("MANGA"));
+    assert_eq!(appearances[1].display_title(), "Cowboy Bebop");
+}
+
+#[tokio::test]
+async fn finds_studio() {
+    let anilist = mock_client().await;
+
+    let studio = anilist
+        .search_studio(STUDIO_QUERY, "Sunrise")
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(studio.name, "Sunrise");
+    assert_eq!(studio.is_animation_studio, Some(true));
+}
+
+#[tokio::test]
+async fn missing_character_is_none() {
+    let anilist = mock_client().await;
+
+    let character = anilist
+        .search_character(CHARACTER_QUERY, "Nobody")
+        .await
+        .unwrap();
+
+    assert!(character.is_none());
+}
+
+#[tokio::test]
+async fn finds_user_statistics() {
+    let anilist = mock_client().await;
+
+    let user = anilist.user(USER_QUERY, "MockUser").await.unwrap().unwrap();
+
+    assert_eq!(user.colour(), 0x004c_ca51);
+
+    let anime = user.statistics.unwrap().anime.unwrap();
+    assert_eq!(anime.count, Some(312));
+    assert_eq!(anime.minutes_watched, Some(98765));
+    assert_eq!(anime.genres[0].genre.as_deref(), Some("Action"));
+}
+
+#[tokio::test]
+async fn seasonal_chart_follows_pages() {
+    let anilist = mock_client().await;
+
+    let results = anilist
+        .seasonal_media(SEASON_QUERY, "SPRING", 1998, "POPULARITY_DESC", 3)
+        .await
+        .unwrap();
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].format.as_deref(), Some("TV"));
+    assert_eq!(results[0].popularity, Some(361_354));
+    assert_eq!(results[2].display_title(), "Trigun");
+}
+
+#[tokio::test]
+async fn seasonal_chart_stops_at_max_pages() {
+    let anilist = mock_client().await;
+
+    let results = anilist
+        .seasonal_media(SEASON_QUERY, "SPRING", 1998, "POPULARITY_DESC", 1)
+        .await
+        .unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[1].display_title(), "Kaikan Phrase");
+}
+
+#[tokio::test]
+async fn finds_relations_and_recommendations() {
+    let anilist = mock_client().await;
+
+    let media = anilist
+        .media_of_type(RELATED_QUERY, None, Some("Bebop Relations"), "ANIME")
+        .await
+        .unwrap()
+        .unwrap();
+
+    let edges = &media.relations.as_ref().unwrap().edges;
+    assert_eq!(edges.len(), 2);
+    assert_eq!(edges[0].relation_type.as_deref(), Some("SIDE_STORY"));
+    assert_eq!(
+        edges[1].node.as_ref().unwrap().media_type.as_deref(),
+        Some("MANGA")
+    );
+
+    let recommendations = &media.recommendations.as_ref().unwrap().nodes;
+    assert_eq!(recommendations[0].rating, Some(1024));
+    assert!(recommendations[1].media_recommendation.is_none());
+}
+
+#[tokio::test]
+async fn trailer_and_streaming_links() {
+    let anilist = mock_client().await;
+
+    let media = first_match(&anilist, ANIME_QUERY, "Cowboy Bebop").await;
+
+    assert_eq!(
+        media.trailer.as_ref().unwrap().url().as_deref(),
+        Some("https://www.youtube.com/watch?v=qig4KOK2R2g")
+    );
+
+    let links = media.external_links.as_ref().unwrap();
+    assert_eq!(links[0].link_type.as_deref(), Some("STREAMING"));
+    assert_eq!(links[1].site, "Official Site");
+    assert_eq!(media.streaming_episodes.as_ref().unwrap().len(), 1);
+}
+
+#[test]
+fn dailymotion_trailers_link_to_dailymotion() {
+    let trailer = MediaTrailer {
+        id: Some(String::from("x7tgad0")),
+        site: Some(String::from("dailymotion")),
+        thumbnail: None,
+    };
+    assert_eq!(
+        trailer.url().as_deref(),
+        Some("https://www.dailymotion.com/video/x7tgad0")
+    );
+
+    let unknown = MediaTrailer {
+        id: Some(String::from("1")),
+        site: Some(String::from("vimeo")),
+        thumbnail: None,
+    };
+    assert_eq!(unknown.url(), None);
+}
+
+#[test]
+fn random_filters_become_inclusive_variables() {
+    let filters = MediaFilters {
+        genres: vec![String::from("Action"), String::from("Sci-Fi")],
+        min_score: Some(80),
+        format: Some(String::from("TV")),
+        year_from: Some(1998),
+        year_to: Some(2004),
+        exclude_adult: true,
+    };
+
+    assert_eq!(
+        filters.variables(),
+        json!({
+            "genres": ["Action", "Sci-Fi"],
+            "minScore": 79,
+            "format": "TV",
+            "startFrom": 19_979_999,
+            "startTo": 20_050_000,
+            "isAdult": false,
+        })
+    );
+}
+
+#[test]
+fn unset_random_filters_are_null() {
+    let variables = MediaFilters::default().variables();
+
+    assert!(variables["genres"].is_null());
+    assert!(variables["minScore"].is_null());
+    assert!(variables["isAdult"].is_null());
+}
+
+#[tokio::test]
+async fn malformed_queries_are_rejected() {
+    let anilist = mock_client().await;
+
+    // Declares `$search` but filters on `$name`, a mistake AniList would reject
+    let query = "query ($search: String) { Page { media (search: $name) { id } } }";
+    let why = anilist
+        .search_media_page(query, "Bebop", 1)
+        .await
+        .unwrap_err();
+
+    assert!(why.to_string().contains("$name"));
+}
@@ -0,0 +1,35 @@
+use std::{sync::Arc, time::Duration};
+
+use rusted_wumpus_lib::autocomplete::TitleAutocomplete;
+
+#[tokio::test]
+async fn lone_keystroke_is_searched() {
+    let autocomplete = TitleAutocomplete::default();
+
+    assert!(autocomplete.debounce(1).await);
+}
+
+#[tokio::test]
+async fn only_last_keystroke_in_a_burst_is_searched() {
+    let autocomplete = Arc::new(TitleAutocomplete::default());
+
+    let first = tokio::spawn({
+        let autocomplete = autocomplete.clone();
+        async move { autocomplete.debounce(1).await }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let second = autocomplete.debounce(1).await;
+
+    assert!(!first.await.unwrap());
+    assert!(second);
+}
+
+#[tokio::test]
+async fn users_are_debounced_separately() {
+    let autocomplete = TitleAutocomplete::default();
+
+    let (first, second) = tokio::join!(autocomplete.debounce(1), autocomplete.debounce(2));
+
+    assert!(first);
+    assert!(second);
+}
@@ -0,0 +1,289 @@
+//! In-process stand-in for the AniList GraphQL API.
+//!
+//! Responses are picked by the `search` (or `name`/`id`) variable of the incoming query, so tests can point
+//! `AniList` at [`MockAniList::url`] and get recorded fixtures back without touching the network. Airing
+//! schedule queries use the `airing` route, trimmed to the requested window like AniList would.
+//!
+//! Queries are checked before a fixture is picked, so a typo in one of the `vars.rs` queries fails the tests
+//! the way AniList would fail the request.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use serde_json::Value;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+/// Body AniList sends back when nothing matches a search.
+pub const NOT_FOUND: &str = include_str!("../fixtures/anilist/not_found.json");
+
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl MockResponse {
+    pub fn ok(body: &str) -> Self {
+        Self::status(200, body)
+    }
+
+    pub fn status(status: u16, body: &str) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.to_string(),
+        }
+    }
+
+    /// AniList's 429 response, asking us to wait `retry_after` seconds.
+    pub fn rate_limited(retry_after: u64) -> Self {
+        let mut resp = Self::status(429, include_str!("../fixtures/anilist/rate_limited.json"));
+        resp.headers = vec![
+            ("Retry-After".to_string(), retry_after.to_string()),
+            ("X-RateLimit-Remaining".to_string(), "0".to_string()),
+        ];
+        resp
+    }
+}
+
+pub struct MockAniList {
+    pub url: String,
+    /// How many requests the server has answered
+    pub requests: Arc<AtomicUsize>,
+}
+
+impl MockAniList {
+    /// Starts the server on a random local port.
+    ///
+    /// Searches that aren't in `routes` get AniList's 404 "Not Found." response.
+    pub async fn start(routes: HashMap<&str, MockResponse>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let routes: Arc<HashMap<String, MockResponse>> = Arc::new(
+            routes
+                .into_iter()
+                .map(|(search, resp)| (search.to_string(), resp))
+                .collect(),
+        );
+
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(handle(socket, routes.clone()));
+            }
+        });
+
+        Self {
+            url: format!("http://{addr}/"),
+            requests,
+        }
+    }
+
+    pub fn request_count(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+}
+
+async fn handle(mut socket: TcpStream, routes: Arc<HashMap<String, MockResponse>>) {
+    let body = serde_json::from_str::<Value>(&read_body(&mut socket).await).unwrap_or_default();
+
+    let resp = match check_query(
+        body["query"].as_str().unwrap_or_default(),
+        &body["variables"],
+    ) {
+        Ok(()) => {
+            let key = route_key(&body["variables"]).unwrap_or_default();
+            let mut resp = routes
+                .get(&key)
+                .cloned()
+                .unwrap_or_else(|| MockResponse::status(404, NOT_FOUND));
+            if key == "airing" && resp.status == 200 {
+                resp.body = airing_window(&resp.body, &body["variables"]);
+            }
+            resp
+        }
+        Err(why) => MockResponse::status(
+            400,
+            &serde_json::json!({ "data": null, "errors": [{ "message": why, "status": 400 }] })
+                .to_string(),
+        ),
+    };
+
+    let headers: String = resp
+        .headers
+        .iter()
+        .map(|(name, value)| format!("{name}: {value}\r\n"))
+        .collect();
+
+    let reply = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{headers}Connection: close\r\n\r\n{}",
+        resp.status,
+        resp.body.len(),
+        resp.body
+    );
+
+    socket.write_all(reply.as_bytes()).await.unwrap();
+    socket.shutdown().await.ok();
+}
+
+/// Picks the route for a query, the `search` or `name` variable, `id:<id>` for lookups by ID or `airing` for airing schedules.
+fn route_key(variables: &Value) -> Option<String> {
+    variables["search"]
+        .as_str()
+        .or_else(|| variables["name"].as_str())
+        .map(String::from)
+        .or_else(|| variables["id"].as_i64().map(|id| format!("id:{id}")))
+        .or_else(|| {
+            variables["mediaIds"]
+                .is_array()
+                .then(|| String::from("airing"))
+        })
+        .or_else(|| {
+            variables["season"].as_str().map(|season| {
+                format!(
+                    "season:{season}:{}:{}",
+                    variables["seasonYear"], variables["page"]
+                )
+            })
+        })
+}
+
+/// Keeps the airing schedules of `body` that aired strictly between the `from` and `to` variables.
+fn airing_window(body: &str, variables: &Value) -> String {
+    let mut body: Value = serde_json::from_str(body).unwrap();
+    let from = variables["from"].as_i64().unwrap_or(i64::MIN);
+    let to = variables["to"].as_i64().unwrap_or(i64::MAX);
+
+    if let Some(schedules) = body["data"]["Page"]["airingSchedules"].as_array_mut() {
+        schedules.retain(|s| {
+            let airing_at = s["airingAt"].as_i64().unwrap_or_default();
+            airing_at > from && airing_at < to
+        });
+    }
+
+    body.to_string()
+}
+
+/// The GraphQL validation AniList does that a typo is most likely to trip: brackets have to balance, every
+/// variable used has to be declared, every declared variable used, and every variable sent declared.
+fn check_query(query: &str, variables: &Value) -> Result<(), String> {
+    // Comments run from `#` to the end of the line
+    let query: String = query
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut open = Vec::new();
+    for c in query.chars() {
+        match c {
+            '{' | '(' | '[' => open.push(c),
+            '}' | ')' | ']' => {
+                let expected = match c {
+                    '}' => '{',
+                    ')' => '(',
+                    _ => '[',
+                };
+                if open.pop() != Some(expected) {
+                    return Err(format!("Syntax Error: Unexpected \"{c}\""));
+                }
+            }
+            _ => {}
+        }
+    }
+    if !open.is_empty() {
+        return Err(String::from("Syntax Error: Expected Name, found <EOF>"));
+    }
+
+    // Declarations are the `$name: Type` pairs before the selection set
+    let selection_start = query.find('{').ok_or("Syntax Error: Expected {")?;
+    let (header, selection) = query.split_at(selection_start);
+    let declared = variable_names(header);
+    let used = variable_names(selection);
+
+    let mut problems: Vec<String> = Vec::new();
+    problems.extend(
+        used.difference(&declared)
+            .map(|name| format!("Variable \"${name}\" is not defined.")),
+    );
+    problems.extend(
+        declared
+            .difference(&used)
+            .map(|name| format!("Variable \"${name}\" is never used.")),
+    );
+    if let Some(sent) = variables.as_object() {
+        problems.extend(
+            sent.keys()
+                .filter(|name| !declared.contains(name.as_str()))
+                .map(|name| format!("Variable \"${name}\" was sent but isn't declared.")),
+        );
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        problems.sort();
+        Err(problems.join(" "))
+    }
+}
+
+fn variable_names(text: &str) -> HashSet<&str> {
+    text.split('$')
+        .skip(1)
+        .map(|rest| {
+            let end = rest
+                .find(|c: char| !c.is_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            &rest[..end]
+        })
+        .collect()
+}
+
+/// Reads a single HTTP/1.1 request and returns its body.
+async fn read_body(socket: &mut TcpStream) -> String {
+    let mut buf = Vec::new();
+    let mut chunk = [0_u8; 4096];
+
+    // Read until the end of the headers
+    let header_end = loop {
+        let n = socket.read(&mut chunk).await.unwrap();
+        if n == 0 {
+            return String::new();
+        }
+        buf.extend_from_slice(&chunk[..n]);
+
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+    };
+
+    let headers = String::from_utf8_lossy(&buf[..header_end]).to_lowercase();
+    let content_length = headers
+        .lines()
+        .find_map(|l| l.strip_prefix("content-length:"))
+        .and_then(|l| l.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+
+    while buf.len() < header_end + content_length {
+        let n = socket.read(&mut chunk).await.unwrap();
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    String::from_utf8_lossy(&buf[header_end..]).into_owned()
+}
@@ -0,0 +1,134 @@
+use rusted_wumpus_lib::{
+    export::{export_one, export_rows, flatten, import_rows, ExportFormat},
+    structs::QuoteImport,
+};
+use serde_json::{json, Value};
+
+/// The entry as AniList sends it, which is what `anime raw:True` exports.
+fn cowboy_bebop() -> Value {
+    let body: Value =
+        serde_json::from_str(include_str!("fixtures/anilist/anime_cowboy_bebop.json")).unwrap();
+    body["data"]["Page"]["media"][0].clone()
+}
+
+#[test]
+fn json_is_anilist_shaped() {
+    let exported = export_one(&cowboy_bebop(), ExportFormat::Json).unwrap();
+    let value: Value = serde_json::from_str(&exported).unwrap();
+
+    assert_eq!(value["id"], 1);
+    assert_eq!(value["seasonYear"], 1998);
+    // Fields ANIME_QUERY doesn't ask for aren't made up as nulls
+    assert!(value.get("type").is_none());
+    assert!(value.get("volumes").is_none());
+    assert_eq!(value["coverImage"]["color"], "#f1785d");
+    assert!(exported.contains('\n'), "JSON should be pretty printed");
+}
+
+#[test]
+fn yaml_round_trips() {
+    let exported = export_one(&cowboy_bebop(), ExportFormat::Yaml).unwrap();
+    let value: Value = serde_yaml::from_str(&exported).unwrap();
+
+    assert_eq!(value["title"]["romaji"], "Cowboy Bebop");
+    assert_eq!(value["averageScore"], 86);
+}
+
+#[test]
+fn csv_is_one_flattened_row() {
+    let exported = export_one(&cowboy_bebop(), ExportFormat::Csv).unwrap();
+    let mut reader = csv::Reader::from_reader(exported.as_bytes());
+
+    let header = reader.headers().unwrap().clone();
+    let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    assert_eq!(rows.len(), 1);
+
+    let cell = |column: &str| {
+        let index = header.iter().position(|c| c == column).unwrap();
+        rows[0][index].to_string()
+    };
+
+    assert_eq!(cell("title.romaji"), "Cowboy Bebop");
+    assert_eq!(cell("startDate.year"), "1998");
+    assert_eq!(cell("genres"), "Action; Adventure; Drama; Sci-Fi");
+    assert_eq!(cell("hashtag"), "");
+}
+
+#[test]
+fn csv_header_covers_every_row() {
+    let rows = vec![
+        json!({ "id": 1 }),
+        json!({ "id": 2, "extra": { "note": "x" } }),
+    ];
+    let exported = export_rows(&rows, ExportFormat::Csv).unwrap();
+
+    assert_eq!(exported, "id,extra.note\n1,\n2,x\n");
+}
+
+#[test]
+fn nested_lists_are_indexed() {
+    let columns = flatten(&json!({ "nodes": [{ "id": 1 }, { "id": 2 }] }));
+
+    assert_eq!(
+        columns,
+        vec![
+            (String::from("nodes.0.id"), String::from("1")),
+            (String::from("nodes.1.id"), String::from("2")),
+        ]
+    );
+}
+
+#[test]
+fn csv_quotes_import_with_missing_columns() {
+    let data = "id,quote,said_by_name,created_at\nabc123,Hello there,Alice,2026-03-01T12:00:00Z\n,General Kenobi,,\n";
+    let rows: Vec<Result<QuoteImport, String>> = import_rows(data, ExportFormat::Csv).unwrap();
+
+    assert_eq!(rows.len(), 2);
+    let first = rows[0].as_ref().unwrap();
+    assert_eq!(first.id.as_deref(), Some("abc123"));
+    assert_eq!(first.said_by_name.as_deref(), Some("Alice"));
+    assert_eq!(
+        first.created_at.unwrap().format("%Y-%m-%d").to_string(),
+        "2026-03-01"
+    );
+    assert_eq!(first.added_by, None);
+
+    let second = rows[1].as_ref().unwrap();
+    assert_eq!(second.id, None);
+    assert_eq!(second.quote, "General Kenobi");
+    assert_eq!(second.created_at, None);
+}
+
+#[test]
+fn bad_json_rows_dont_stop_the_rest() {
+    let data = r#"[{ "quote": "one" }, { "quote": 2 }, { "quote": "three", "said_by_id": "42" }]"#;
+    let rows: Vec<Result<QuoteImport, String>> = import_rows(data, ExportFormat::Json).unwrap();
+
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0].as_ref().unwrap().quote, "one");
+    assert!(rows[1].is_err());
+    assert_eq!(rows[2].as_ref().unwrap().said_by_id.as_deref(), Some("42"));
+}
+
+#[test]
+fn json_that_isnt_a_list_fails() {
+    let rows = import_rows::<QuoteImport>(r#"{ "quote": "one" }"#, ExportFormat::Json);
+
+    assert!(rows.is_err());
+}
+
+#[test]
+fn csv_byte_order_mark_is_ignored() {
+    let data = "\u{feff}id,quote\nabc123,Hello there\n";
+    let rows: Vec<Result<QuoteImport, String>> = import_rows(data, ExportFormat::Csv).unwrap();
+
+    assert_eq!(rows[0].as_ref().unwrap().id.as_deref(), Some("abc123"));
+}
+
+#[test]
+fn exported_guild_ids_dont_stop_an_import() {
+    let data = "id,quote,guild_id\nabc123,Hello there,42\n";
+    let rows: Vec<Result<QuoteImport, String>> = import_rows(data, ExportFormat::Csv).unwrap();
+
+    assert_eq!(rows[0].as_ref().unwrap().quote, "Hello there");
+}